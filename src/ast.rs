@@ -4,18 +4,44 @@ use crate::TokenType;
 
 pub type Program = Vec<Stmt>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct BlockStatement(pub Vec<Stmt>);
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// Arguments of a function call
+#[derive(Debug, PartialEq, Clone)]
 pub struct Parameters(pub Vec<Expr>);
+
+/// Parameter of a function definition, either a plain identifier, an identifier with a default
+/// value (`b = 2`) or a rest parameter (`...rest`) collecting the remaining arguments.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Param {
+    pub name: Ident,
+    pub default: Option<Expr>,
+    pub rest: bool,
+}
+
+impl Param {
+    pub fn new(name: Ident) -> Param {
+        Param {
+            name,
+            default: None,
+            rest: false,
+        }
+    }
+}
 //pub type BlockStatement = Vec<Stmt>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Stmt {
     LET(Ident, Expr),
     RETURN(Expr),
     EXPRESSION(Expr),
+    DOCUMENTED(String, Box<Stmt>),
+    BLOCK(BlockStatement),
+    WHILE(Expr, BlockStatement),
+    FOR(Ident, Expr, BlockStatement),
+    BREAK,
+    CONTINUE,
 }
 impl fmt::Display for Stmt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -23,6 +49,19 @@ impl fmt::Display for Stmt {
             Stmt::LET(var_name, expr) => write!(f, "LET {} = {};", var_name, expr),
             Stmt::RETURN(expr) => write!(f, "RETURN {};", expr),
             Stmt::EXPRESSION(expr) => write!(f, "{}", expr),
+            Stmt::DOCUMENTED(doc, stmt) => {
+                for line in doc.lines() {
+                    writeln!(f, "/// {}", line)?;
+                }
+                write!(f, "{}", stmt)
+            }
+            Stmt::BLOCK(block) => write!(f, "{{ {} }}", block),
+            Stmt::WHILE(cond, body) => write!(f, "WHILE {} {{ {} }}", cond, body),
+            Stmt::FOR(var_name, iterable, body) => {
+                write!(f, "FOR {} IN {} {{ {} }}", var_name, iterable, body)
+            }
+            Stmt::BREAK => write!(f, "BREAK;"),
+            Stmt::CONTINUE => write!(f, "CONTINUE;"),
         }
     }
 }
@@ -35,6 +74,16 @@ impl fmt::Display for Parameters {
     }
 }
 
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.default, self.rest) {
+            (_, true) => write!(f, "...{}", self.name),
+            (Some(default), false) => write!(f, "{} = {}", self.name, default),
+            (None, false) => write!(f, "{}", self.name),
+        }
+    }
+}
+
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -42,25 +91,52 @@ impl fmt::Display for Expr {
             Expr::BOOLEAN(false) => write!(f, "false"),
             Expr::IDENTIFIER(ident) => write!(f, "{}", ident),
             Expr::INTEGER(integ) => write!(f, "{}", integ),
+            Expr::FLOAT(float) => write!(f, "{:?}", float),
+            Expr::STRING(string) => write!(f, "\"{}\"", string),
             Expr::BANG(expr) => write!(f, "(!{})", expr),
             Expr::MINUS(expr) => write!(f, "(-{})", expr),
+            Expr::PREFIX(operator, expr) => write!(f, "({} {})", operator, expr),
             Expr::INFIX(inf_expr, operator, post_expr) => {
                 write!(f, "({} {} {})", *inf_expr, operator, *post_expr)
             }
             Expr::IF(expr, conseq, alter) => {
-                let mut ifstmt = format!("if {} {{ {} }}", expr, conseq);
-                let BlockStatement(alter_stmts) = alter;
-                if alter_stmts.is_empty() {
-                    ifstmt.push_str(format!("else{{ {} }}", alter).as_str());
+                write!(f, "if {} {{ {} }}", expr, conseq)?;
+                match alter {
+                    None => Ok(()),
+                    Some(alter) => match alter.0.as_slice() {
+                        // else if chains are stored as an alternative holding only the next if
+                        [Stmt::EXPRESSION(else_if @ Expr::IF(..))] => {
+                            write!(f, " else {}", else_if)
+                        }
+                        _ => write!(f, " else {{ {} }}", alter),
+                    },
                 }
-                write!(f, "{}", ifstmt)
             }
             Expr::FUNC(params, stmts) => {
-                write!(f, "fn ({}){{ {} }}", params, stmts)
+                let params_string: Vec<String> = params.iter().map(|n| n.to_string()).collect();
+                write!(f, "fn ({}){{ {} }}", params_string.join(","), stmts)
             }
             Expr::CALL(func, params) => {
                 write!(f, "{}({})", func, params)
             }
+            Expr::ARRAY(elements) => {
+                let elements_string: Vec<String> = elements.iter().map(|n| n.to_string()).collect();
+                write!(f, "[{}]", elements_string.join(", "))
+            }
+            Expr::HASH(pairs) => {
+                let pairs_string: Vec<String> = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value))
+                    .collect();
+                write!(f, "{{{}}}", pairs_string.join(", "))
+            }
+            Expr::ASSIGN(target, None, value) => write!(f, "({} = {})", target, value),
+            Expr::ASSIGN(target, Some(operator), value) => {
+                write!(f, "({} {}= {})", target, operator, value)
+            }
+            Expr::RANGE(start, end, false) => write!(f, "({}..{})", start, end),
+            Expr::RANGE(start, end, true) => write!(f, "({}..={})", start, end),
+            Expr::INDEX(expr, index) => write!(f, "({}[{}])", expr, index),
         }
     }
 }
@@ -75,17 +151,32 @@ impl fmt::Display for BlockStatement {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expr {
     IDENTIFIER(Ident),
     INTEGER(usize),
+    FLOAT(f64),
+    STRING(String),
     BANG(Box<Expr>),
     MINUS(Box<Expr>),
+    /// Prefix operator registered by a dialect, named after its keyword, and its operand
+    PREFIX(String, Box<Expr>),
     INFIX(Box<Expr>, Infix, Box<Expr>),
     BOOLEAN(bool),
-    IF(Box<Expr>, BlockStatement, BlockStatement),
-    FUNC(Parameters, BlockStatement),
+    /// Condition, consequence and alternative, `else if` being an alternative holding the next if
+    IF(Box<Expr>, BlockStatement, Option<BlockStatement>),
+    FUNC(Vec<Param>, BlockStatement),
     CALL(Box<Expr>, Parameters),
+    ARRAY(Vec<Expr>),
+    /// Keys and values of a hash literal, in the order of the source code
+    HASH(Vec<(Expr, Expr)>),
+    /// Indexed expression and index
+    INDEX(Box<Expr>, Box<Expr>),
+    /// Assigned target, either an identifier or an index expression, operator of a compound
+    /// assignment (e.g. `PLUS` for `+=`) and assigned value
+    ASSIGN(Box<Expr>, Option<Infix>, Box<Expr>),
+    /// Start and end of a range, the end being included for `..=`
+    RANGE(Box<Expr>, Box<Expr>, bool),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -101,12 +192,30 @@ impl fmt::Display for Ident {
 #[derive(Debug, PartialEq, PartialOrd, Eq, Clone)]
 pub enum Precedence {
     LOWEST,
+    ASSIGN,
+    RANGE,
+    OR,
+    AND,
     EQUALS,
     LESSGREATER,
+    BITOR,
+    BITXOR,
+    BITAND,
+    SHIFT,
     SUM,
     PRODUCT,
     PREFIX,
+    POWER,
     CALL,
+    INDEX,
+}
+
+/// Associativity of an infix operator: `a op b op c` is `(a op b) op c` for a left associative
+/// operator and `a op (b op c)` for a right associative one.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Associativity {
+    LEFT,
+    RIGHT,
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Ord, Eq, Clone)]
@@ -119,6 +228,21 @@ pub enum Infix {
     LT,
     EQUAL,
     NOTEQUAL,
+    LTEQUAL,
+    GTEQUAL,
+    MODULO,
+    POWER,
+    /// Short-circuit logical and, the right member is only evaluated if the left one is true
+    AND,
+    /// Short-circuit logical or, the right member is only evaluated if the left one is false
+    OR,
+    BITAND,
+    BITOR,
+    BITXOR,
+    LSHIFT,
+    RSHIFT,
+    /// Infix operator registered by a dialect, named after its keyword
+    CUSTOM(String),
     ILLEGAL,
 }
 
@@ -133,6 +257,18 @@ impl From<TokenType> for Infix {
             TokenType::LT => Infix::LT,
             TokenType::EQUAL => Infix::EQUAL,
             TokenType::NOTEQUAL => Infix::NOTEQUAL,
+            TokenType::LTEQUAL => Infix::LTEQUAL,
+            TokenType::GTEQUAL => Infix::GTEQUAL,
+            TokenType::PERCENT => Infix::MODULO,
+            TokenType::POWER => Infix::POWER,
+            TokenType::AND => Infix::AND,
+            TokenType::OR => Infix::OR,
+            TokenType::AMPERSAND => Infix::BITAND,
+            TokenType::PIPE => Infix::BITOR,
+            TokenType::CARET => Infix::BITXOR,
+            TokenType::LSHIFT => Infix::LSHIFT,
+            TokenType::RSHIFT => Infix::RSHIFT,
+            TokenType::KEYWORD(keyword) => Infix::CUSTOM(keyword),
             _ => Infix::ILLEGAL,
         }
     }
@@ -149,6 +285,18 @@ impl fmt::Display for Infix {
             Infix::LT => write!(f, "<"),
             Infix::EQUAL => write!(f, "=="),
             Infix::NOTEQUAL => write!(f, "!="),
+            Infix::LTEQUAL => write!(f, "<="),
+            Infix::GTEQUAL => write!(f, ">="),
+            Infix::MODULO => write!(f, "%"),
+            Infix::POWER => write!(f, "**"),
+            Infix::AND => write!(f, "&&"),
+            Infix::OR => write!(f, "||"),
+            Infix::BITAND => write!(f, "&"),
+            Infix::BITOR => write!(f, "|"),
+            Infix::BITXOR => write!(f, "^"),
+            Infix::LSHIFT => write!(f, "<<"),
+            Infix::RSHIFT => write!(f, ">>"),
+            Infix::CUSTOM(keyword) => write!(f, "{}", keyword),
             Infix::ILLEGAL => write!(f, "$$ILLEGALCARACT$$"),
         }
     }
@@ -0,0 +1,129 @@
+//! Zero-copy flavour of the lexer: tokens borrow their text from the source code and identifiers
+//! are interned in a symbol table, so that lexing does not allocate for each token.
+use std::borrow::Cow;
+use std::iter::FusedIterator;
+
+use crate::error::{LexError, LexWarning};
+use crate::lexer::{doc_comment_text, Lexer};
+use crate::symbol::{Symbol, SymbolTable};
+use crate::token::{Span, Token, TokenType};
+
+/// Type of a borrowed token. Tokens carrying text have their own variant, every other token is
+/// described by its `TokenType`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BorrowedTokenType<'src> {
+    IDENT(Symbol),
+    /// Borrowed from the source unless the string contains escape sequences
+    STRING(Cow<'src, str>),
+    DOC(&'src str),
+    TOKEN(TokenType),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BorrowedToken<'src> {
+    pub tokentype: BorrowedTokenType<'src>,
+    pub literal: &'src str,
+    pub span: Span,
+}
+
+impl<'src> BorrowedToken<'src> {
+    /// Converts the token to an owned `Token`, resolving identifiers with the given table
+    pub fn to_token(&self, symbols: &SymbolTable<'src>) -> Token {
+        let tokentype = match &self.tokentype {
+            BorrowedTokenType::IDENT(symbol) => {
+                TokenType::IDENT(symbols.resolve(*symbol).unwrap_or_default().to_string())
+            }
+            BorrowedTokenType::STRING(value) => TokenType::STRING(value.to_string()),
+            BorrowedTokenType::DOC(doc) => TokenType::DOC(doc.to_string()),
+            BorrowedTokenType::TOKEN(tokentype) => tokentype.clone(),
+        };
+        Token::with_span(tokentype, self.literal.to_string(), self.span)
+    }
+}
+
+/// Lexer producing `BorrowedToken`s from a code borrowed for the `'src` lifetime
+pub struct BorrowedLexer<'src> {
+    input: &'src str,
+    lexer: Lexer<'src>,
+    symbols: SymbolTable<'src>,
+    done: bool,
+}
+
+impl<'src> BorrowedLexer<'src> {
+    pub fn new(input: &'src str) -> BorrowedLexer<'src> {
+        let mut lexer = Lexer::new(input);
+        lexer.set_capture(false);
+        BorrowedLexer {
+            input,
+            lexer,
+            symbols: SymbolTable::new(),
+            done: false,
+        }
+    }
+
+    /// Processes the next char(s) and return the next token
+    pub fn next_token(&mut self) -> BorrowedToken<'src> {
+        let token = self.lexer.next_token();
+        let literal = &self.input[token.span.start..token.span.end];
+        let tokentype = match token.tokentype {
+            TokenType::IDENT(_) => BorrowedTokenType::IDENT(self.symbols.intern(literal)),
+            TokenType::STRING(value) => {
+                if value.is_empty() {
+                    // The lexer only copies strings with escape sequences, which are never empty
+                    let content = literal.trim_start_matches('r');
+                    BorrowedTokenType::STRING(Cow::Borrowed(&content[1..content.len() - 1]))
+                } else {
+                    BorrowedTokenType::STRING(Cow::Owned(value))
+                }
+            }
+            TokenType::DOC(_) => BorrowedTokenType::DOC(doc_comment_text(literal)),
+            tokentype => BorrowedTokenType::TOKEN(tokentype),
+        };
+        BorrowedToken {
+            tokentype,
+            literal,
+            span: token.span,
+        }
+    }
+
+    /// Table of the identifiers met so far
+    pub fn symbols(&self) -> &SymbolTable<'src> {
+        &self.symbols
+    }
+
+    /// Consumes the lexer, keeping the table of the identifiers it met
+    pub fn into_symbols(self) -> SymbolTable<'src> {
+        self.symbols
+    }
+
+    /// Check wether errors were met while lexing the input
+    pub fn has_errors(&self) -> bool {
+        self.lexer.has_errors()
+    }
+
+    /// Returns the errors met so far while lexing the input
+    pub fn get_errors(&self) -> Vec<LexError> {
+        self.lexer.get_errors()
+    }
+
+    /// Returns the warnings met so far while lexing the input
+    pub fn get_warnings(&self) -> Vec<LexWarning> {
+        self.lexer.get_warnings()
+    }
+}
+
+/// Iterating over the lexer gives every token of the code, the last one being the EOF token.
+impl<'src> Iterator for BorrowedLexer<'src> {
+    type Item = BorrowedToken<'src>;
+
+    fn next(&mut self) -> Option<BorrowedToken<'src>> {
+        if self.done {
+            return None;
+        }
+        let token = self.next_token();
+        self.done = token.tokentype == BorrowedTokenType::TOKEN(TokenType::EOF);
+        Some(token)
+    }
+}
+
+impl FusedIterator for BorrowedLexer<'_> {}
@@ -0,0 +1,323 @@
+use core::fmt;
+
+use crate::ast::Expr;
+use crate::token::{Span, TokenType};
+
+/// Kind of literal that can be left unterminated
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LiteralKind {
+    String,
+    BlockComment,
+}
+
+impl fmt::Display for LiteralKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiteralKind::String => write!(f, "string literal"),
+            LiteralKind::BlockComment => write!(f, "block comment"),
+        }
+    }
+}
+
+/// Error met by the lexer. Each error knows where it happened and the text that caused it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LexError {
+    /// A char that does not start any token, or a control char in strict mode
+    UnexpectedChar { found: char, span: Span },
+    /// The end of the input was met before the end of the literal
+    UnterminatedLiteral {
+        kind: LiteralKind,
+        text: String,
+        span: Span,
+    },
+    /// Unknown or malformed escape sequence in a string literal
+    InvalidEscape { text: String, span: Span },
+    /// Digit that does not belong to the base of an integer literal (e.g. `0b102`)
+    InvalidDigit {
+        found: char,
+        radix: u32,
+        text: String,
+        span: Span,
+    },
+    /// Radix prefix without any digit (e.g. `0x`)
+    MissingDigits { text: String, span: Span },
+    /// Integer literal too large to fit an integer
+    NumberOverflow { text: String, span: Span },
+    /// The reader given to the lexer failed
+    Io { message: String, span: Span },
+    /// Unknown `#!` pragma, or unknown edition in a `#!edition` pragma
+    InvalidPragma { text: String, span: Span },
+}
+
+impl LexError {
+    /// Location of the error in the source code
+    pub fn span(&self) -> Span {
+        match self {
+            LexError::UnexpectedChar { span, .. }
+            | LexError::UnterminatedLiteral { span, .. }
+            | LexError::InvalidEscape { span, .. }
+            | LexError::InvalidDigit { span, .. }
+            | LexError::MissingDigits { span, .. }
+            | LexError::NumberOverflow { span, .. }
+            | LexError::Io { span, .. }
+            | LexError::InvalidPragma { span, .. } => *span,
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexError::UnexpectedChar { found, span } if found.is_control() => write!(
+                f,
+                "{}: Unexpected control character U+{:04X}",
+                span, *found as u32
+            ),
+            LexError::UnexpectedChar { found, span } => {
+                write!(f, "{}: Unexpected character {}", span, found)
+            }
+            LexError::UnterminatedLiteral { kind, span, .. } => {
+                write!(f, "{}: Unterminated {}", span, kind)
+            }
+            LexError::InvalidEscape { text, span } => {
+                write!(f, "{}: Invalid escape sequence {}", span, text)
+            }
+            LexError::InvalidDigit {
+                found,
+                radix,
+                text,
+                span,
+            } => write!(
+                f,
+                "{}: Invalid digit {} in base {} literal {}",
+                span, found, radix, text
+            ),
+            LexError::MissingDigits { text, span } => {
+                write!(f, "{}: Missing digits after {}", span, text)
+            }
+            LexError::NumberOverflow { text, span } => write!(
+                f,
+                "{}: Integer literal {} is too large, maximum is {}",
+                span,
+                text,
+                usize::MAX
+            ),
+            LexError::Io { message, span } => {
+                write!(f, "{}: Could not read input: {}", span, message)
+            }
+            LexError::InvalidPragma { text, span } => {
+                write!(f, "{}: Invalid pragma {}", span, text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LexError {}
+
+/// Warning met by the lexer, the input is still lexed as the user intended
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LexWarning {
+    /// Legacy spelling of a keyword used in the strict edition (e.g. `LET` instead of `let`)
+    DeprecatedKeyword {
+        found: String,
+        keyword: String,
+        span: Span,
+    },
+}
+
+impl LexWarning {
+    /// Location of the warning in the source code
+    pub fn span(&self) -> Span {
+        match self {
+            LexWarning::DeprecatedKeyword { span, .. } => *span,
+        }
+    }
+}
+
+impl fmt::Display for LexWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexWarning::DeprecatedKeyword {
+                found,
+                keyword,
+                span,
+            } => write!(
+                f,
+                "{}: Keyword {} is deprecated, use {} instead",
+                span, found, keyword
+            ),
+        }
+    }
+}
+
+/// Error met by the parser. Errors met by the lexer are reported as `ParseError::Lex`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseError {
+    /// Error met while lexing the code
+    Lex(LexError),
+    /// The token found is not the one expected by the grammar. Only the kind of the expected
+    /// token matters, its value (e.g. the name of an `IDENT`) is a placeholder.
+    UnexpectedToken {
+        expected: TokenType,
+        found: TokenType,
+        span: Span,
+    },
+    /// No expression can start with the token found
+    MissingExpression { found: TokenType, span: Span },
+    /// The end of the code was met before the closing delimiter. The span is the one of the
+    /// opening delimiter.
+    UnclosedDelimiter { delimiter: TokenType, span: Span },
+    /// Custom keyword without any registered statement parser
+    UnknownStatement { keyword: String, span: Span },
+    /// Token registered with a built-in parse function which has no operator for it
+    UnknownOperator { found: TokenType, span: Span },
+    /// Function parameter which is not an identifier
+    InvalidParameter { found: TokenType, span: Span },
+    /// Function parameter declared twice
+    DuplicateParameter { name: String, span: Span },
+    /// Rest parameter which is not the last parameter of the function
+    MisplacedRestParameter { name: String, span: Span },
+    /// Assignment to an expression which is neither an identifier nor an index expression
+    InvalidAssignmentTarget { target: Expr, span: Span },
+    /// Range used as the start of another range (e.g. `a..b..c`), which is ambiguous
+    ChainedRange { span: Span },
+    /// `break` or `continue` used outside the body of a loop
+    OutsideLoop { keyword: String, span: Span },
+    /// Expression or block nested too deeply to be parsed without exhausting the stack
+    NestingTooDeep { span: Span },
+}
+
+impl ParseError {
+    /// Location of the error in the source code
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::Lex(error) => error.span(),
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::MissingExpression { span, .. }
+            | ParseError::UnclosedDelimiter { span, .. }
+            | ParseError::UnknownStatement { span, .. }
+            | ParseError::UnknownOperator { span, .. }
+            | ParseError::InvalidParameter { span, .. }
+            | ParseError::DuplicateParameter { span, .. }
+            | ParseError::MisplacedRestParameter { span, .. }
+            | ParseError::InvalidAssignmentTarget { span, .. }
+            | ParseError::ChainedRange { span }
+            | ParseError::OutsideLoop { span, .. }
+            | ParseError::NestingTooDeep { span } => *span,
+        }
+    }
+}
+
+impl From<LexError> for ParseError {
+    fn from(error: LexError) -> ParseError {
+        ParseError::Lex(error)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Lex(error) => error.fmt(f),
+            ParseError::UnexpectedToken {
+                expected,
+                found,
+                span,
+            } => write!(
+                f,
+                "{}: Expected token {} was not found - Found {:?} instead",
+                span,
+                expected.kind_name(),
+                found
+            ),
+            ParseError::MissingExpression { found, span } => write!(
+                f,
+                "{}: Expected an expression - Found {:?} instead",
+                span, found
+            ),
+            ParseError::UnclosedDelimiter { delimiter, span } => {
+                write!(f, "{}: Unclosed delimiter {:?}", span, delimiter)
+            }
+            ParseError::UnknownStatement { keyword, span } => write!(
+                f,
+                "{}: No statement parser registered for keyword {:?}",
+                span, keyword
+            ),
+            ParseError::UnknownOperator { found, span } => {
+                write!(f, "{}: Token {:?} is not an operator", span, found)
+            }
+            ParseError::InvalidParameter { found, span } => write!(
+                f,
+                "{}: Expected a parameter name - Found {:?} instead",
+                span, found
+            ),
+            ParseError::DuplicateParameter { name, span } => {
+                write!(f, "{}: Parameter {} is declared twice", span, name)
+            }
+            ParseError::MisplacedRestParameter { name, span } => write!(
+                f,
+                "{}: Rest parameter {} must be the last parameter",
+                span, name
+            ),
+            ParseError::InvalidAssignmentTarget { target, span } => {
+                write!(f, "{}: Cannot assign to {}", span, target)
+            }
+            ParseError::ChainedRange { span } => {
+                write!(f, "{}: Ranges cannot be chained, use parentheses", span)
+            }
+            ParseError::OutsideLoop { keyword, span } => {
+                write!(f, "{}: {} can only be used inside a loop", span, keyword)
+            }
+            ParseError::NestingTooDeep { span } => {
+                write!(f, "{}: Code is nested too deeply", span)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Lex(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Warning met by the parser, the code is still parsed. Warnings met by the lexer are reported
+/// as `ParseWarning::Lex`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseWarning {
+    /// Warning met while lexing the code
+    Lex(LexWarning),
+    /// Doc comment which is not followed by a let statement, and is therefore ignored
+    DanglingDocComment { found: TokenType, span: Span },
+}
+
+impl ParseWarning {
+    /// Location of the warning in the source code
+    pub fn span(&self) -> Span {
+        match self {
+            ParseWarning::Lex(warning) => warning.span(),
+            ParseWarning::DanglingDocComment { span, .. } => *span,
+        }
+    }
+}
+
+impl From<LexWarning> for ParseWarning {
+    fn from(warning: LexWarning) -> ParseWarning {
+        ParseWarning::Lex(warning)
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::Lex(warning) => warning.fmt(f),
+            ParseWarning::DanglingDocComment { found, span } => write!(
+                f,
+                "{}: Doc comment is not followed by a let statement - Found {:?} instead",
+                span, found
+            ),
+        }
+    }
+}
@@ -1,38 +1,161 @@
 use std::collections::HashMap;
 
 use crate::token::{Token, TokenType};
+
+/// Edition of the language, selected with a leading `#!edition <number>` pragma
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Edition {
+    /// Edition 1: keywords can be written in lowercase or uppercase (e.g. `let` and `LET`)
+    LEGACY,
+    /// Edition 2: keywords are lowercase, uppercase forms are deprecated
+    STRICT,
+}
+
+impl Edition {
+    /// Edition matching the number given in the `#!edition` pragma
+    pub fn from_number(number: u32) -> Option<Edition> {
+        match number {
+            1 => Some(Edition::LEGACY),
+            2 => Some(Edition::STRICT),
+            _ => None,
+        }
+    }
+}
+
+/// Registry of the keywords of the language, consulted by the lexer for every identifier.
+/// Dialects can register their own keywords, usually as `TokenType::KEYWORD` tokens handled by a
+/// statement parser registered in the `Parser`, or remove some of the default ones.
+#[derive(Debug, Clone)]
 pub struct TokenBuilder {
     keywords: HashMap<String, TokenType>,
+    // Legacy spelling of a keyword, associated to the spelling to use instead
+    deprecated: HashMap<String, String>,
+    edition: Edition,
+}
+
+impl Default for TokenBuilder {
+    /// Registry holding the keywords of the Monkey language
+    fn default() -> TokenBuilder {
+        let mut my_builder = TokenBuilder::new();
+        my_builder.add_keyword("fn", TokenType::FUNCTION);
+        my_builder.add_deprecated_keyword("FN", "fn");
+        my_builder.add_keyword("let", TokenType::LET);
+        my_builder.add_deprecated_keyword("LET", "let");
+        my_builder.add_keyword("if", TokenType::IF);
+        my_builder.add_deprecated_keyword("IF", "if");
+        my_builder.add_keyword("else", TokenType::ELSE);
+        my_builder.add_deprecated_keyword("ELSE", "else");
+        my_builder.add_keyword("return", TokenType::RETURN);
+        my_builder.add_deprecated_keyword("RETURN", "return");
+        my_builder.add_keyword("true", TokenType::TRUE);
+        my_builder.add_deprecated_keyword("TRUE", "true");
+        my_builder.add_keyword("false", TokenType::FALSE);
+        my_builder.add_deprecated_keyword("FALSE", "false");
+        my_builder.add_keyword("while", TokenType::WHILE);
+        my_builder.add_deprecated_keyword("WHILE", "while");
+        my_builder.add_keyword("break", TokenType::BREAK);
+        my_builder.add_deprecated_keyword("BREAK", "break");
+        my_builder.add_keyword("continue", TokenType::CONTINUE);
+        my_builder.add_deprecated_keyword("CONTINUE", "continue");
+        my_builder.add_keyword("for", TokenType::FOR);
+        my_builder.add_deprecated_keyword("FOR", "for");
+        my_builder.add_keyword("in", TokenType::IN);
+        my_builder.add_deprecated_keyword("IN", "in");
+
+        my_builder
+    }
 }
 
 impl TokenBuilder {
-    pub fn default() -> TokenBuilder {
-        let mut my_builder = TokenBuilder {
+    /// Registry without any keyword
+    pub fn new() -> TokenBuilder {
+        TokenBuilder {
             keywords: HashMap::new(),
-        };
-        my_builder
-            .keywords
-            .insert("LET".to_string(), TokenType::LET);
-        my_builder
-            .keywords
-            .insert("FN".to_string(), TokenType::FUNCTION);
-        my_builder
-            .keywords
-            .insert("fn".to_string(), TokenType::FUNCTION);
+            deprecated: HashMap::new(),
+            edition: Edition::LEGACY,
+        }
+    }
+
+    /// Registry holding the keywords of the Monkey language for the given edition
+    pub fn for_edition(edition: Edition) -> TokenBuilder {
+        let mut my_builder = TokenBuilder::default();
+        my_builder.set_edition(edition);
         my_builder
-            .keywords
-            .insert("let".to_string(), TokenType::LET);
+    }
 
-        return my_builder;
+    pub fn set_edition(&mut self, edition: Edition) {
+        self.edition = edition;
     }
 
-    pub fn get_token_from_identifier(self, identifier: &String) -> Token {
-        if let Some(toktype) = self.keywords.get(identifier).cloned() {
-            let mytoken: Token = Token::new(toktype, identifier.clone());
-            mytoken
-        } else {
-            let mytoken: Token = Token::new(TokenType::ILLEGAL, "".to_string());
-            mytoken
+    pub fn get_edition(&self) -> Edition {
+        self.edition
+    }
+
+    /// Register a keyword, replacing the token previously produced by this keyword if any. The
+    /// legacy spellings of the keyword produce the new token as well.
+    pub fn add_keyword(&mut self, keyword: &str, tokentype: TokenType) -> &mut TokenBuilder {
+        self.deprecated.remove(keyword);
+        self.keywords.insert(keyword.to_string(), tokentype);
+        self
+    }
+
+    /// Register a dialect keyword lexed as a `TokenType::KEYWORD` token
+    pub fn add_custom_keyword(&mut self, keyword: &str) -> &mut TokenBuilder {
+        self.add_keyword(keyword, TokenType::KEYWORD(keyword.to_string()))
+    }
+
+    /// Register `legacy` as an alternative spelling of the keyword `keyword`, deprecated in the
+    /// strict edition. Does nothing if `keyword` is not registered.
+    pub fn add_deprecated_keyword(&mut self, legacy: &str, keyword: &str) -> &mut TokenBuilder {
+        if self.keywords.contains_key(keyword) {
+            self.keywords.remove(legacy);
+            self.deprecated
+                .insert(legacy.to_string(), keyword.to_string());
+        }
+        self
+    }
+
+    /// Remove a keyword, which is then lexed as a plain identifier, along with its legacy
+    /// spellings. Removing a legacy spelling keeps the keyword it stands for.
+    pub fn remove_keyword(&mut self, keyword: &str) -> Option<TokenType> {
+        if let Some(tokentype) = self.get_keyword(keyword) {
+            if self.deprecated.remove(keyword).is_none() {
+                self.keywords.remove(keyword);
+                self.deprecated.retain(|_, target| target != keyword);
+            }
+            return Some(tokentype);
         }
+        None
+    }
+
+    /// Returns the spelling to use instead of `identifier` if it is a keyword deprecated in the
+    /// current edition
+    pub fn get_deprecation(&self, identifier: &str) -> Option<&str> {
+        match self.edition {
+            Edition::LEGACY => None,
+            Edition::STRICT => self.deprecated.get(identifier).map(String::as_str),
+        }
+    }
+
+    /// Returns the keyword token matching the identifier, if any. Legacy spellings produce the
+    /// token of the keyword they stand for.
+    pub fn get_keyword(&self, identifier: &str) -> Option<TokenType> {
+        let keyword = self
+            .deprecated
+            .get(identifier)
+            .map_or(identifier, String::as_str);
+        self.keywords.get(keyword).cloned()
+    }
+
+    pub fn is_keyword(&self, identifier: &str) -> bool {
+        self.get_keyword(identifier).is_some()
+    }
+
+    /// Create a token from a identifier given as parameter, either a keyword or an identifier
+    pub fn get_token_from_identifier(&self, identifier: &str) -> Token {
+        let tokentype = self
+            .get_keyword(identifier)
+            .unwrap_or_else(|| TokenType::IDENT(identifier.to_string()));
+        Token::new(tokentype, identifier.to_string())
     }
 }
@@ -0,0 +1,134 @@
+//! Incremental lexing for editors: after an edit of the code, only the tokens around the edited
+//! text are lexed again, the other tokens of the previous lexing are kept.
+use std::ops::Range;
+
+use crate::identifier::TokenBuilder;
+use crate::lexer::Lexer;
+use crate::token::{Span, Token};
+
+/// Replacement of the bytes `range` of the code by `replacement`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new<S: Into<String>>(range: Range<usize>, replacement: S) -> TextEdit {
+        TextEdit {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Apply the edit to the code
+    pub fn apply(&self, code: &mut String) {
+        code.replace_range(self.range.clone(), &self.replacement);
+    }
+
+    /// Difference between the length of the code after and before the edit
+    fn delta(&self) -> isize {
+        self.replacement.len() as isize - (self.range.end - self.range.start) as isize
+    }
+}
+
+/// Result of an incremental lexing
+#[derive(Debug, PartialEq, Clone)]
+pub struct Relexed {
+    /// Every token of the edited code, the last one being the EOF token
+    pub tokens: Vec<Token>,
+    /// Indexes in `tokens` of the tokens that were lexed again. The tokens before it are the
+    /// previous ones, the tokens after it are the previous ones moved by the edit.
+    pub changed: Range<usize>,
+}
+
+/// Lex the code again after an edit.
+/// * `code` - Code after the edit
+/// * `tokens` - Tokens of the code before the edit, as returned by the lexer
+/// * `edit` - Edit applied to the code
+pub fn relex(code: &str, tokens: &[Token], edit: &TextEdit) -> Relexed {
+    relex_with_keywords(code, tokens, edit, TokenBuilder::default())
+}
+
+/// Same as `relex` for code lexed with the keywords registered in `keywords`
+pub fn relex_with_keywords(
+    code: &str,
+    tokens: &[Token],
+    edit: &TextEdit,
+    keywords: TokenBuilder,
+) -> Relexed {
+    // The token touching the edit may be merged with the edited text (e.g. `<` followed by an
+    // inserted `=`), so lexing starts again one token before it.
+    let first = tokens
+        .iter()
+        .position(|tok| tok.span.end >= edit.range.start)
+        .unwrap_or(tokens.len())
+        .saturating_sub(1);
+    let mut lexer = Lexer::with_keywords(code, keywords);
+    if first > 0 {
+        let span = tokens[first].span;
+        lexer.seek(span.start, span.line, span.column);
+    }
+
+    let delta = edit.delta();
+    let edit_end = edit.range.start + edit.replacement.len();
+    let mut result: Vec<Token> = tokens[..first].to_vec();
+    let mut old = first;
+    for tok in lexer {
+        if tok.span.start >= edit_end {
+            // Previous tokens starting in the replaced text or before this one will never be met
+            // again. Only the tokens after the replaced text can be moved by the edit.
+            while old < tokens.len()
+                && (tokens[old].span.start < edit.range.end
+                    || shift(tokens[old].span.start, delta) < tok.span.start)
+            {
+                old += 1;
+            }
+            if old < tokens.len() && is_same_token(&tokens[old], &tok, delta) {
+                let changed = first..result.len();
+                let moved = &tokens[old];
+                let lines = tok.span.line as isize - moved.span.line as isize;
+                let columns = tok.span.column as isize - moved.span.column as isize;
+                result.extend(tokens[old..].iter().map(|prev| {
+                    let columns = if prev.span.line == moved.span.line {
+                        columns
+                    } else {
+                        0
+                    };
+                    let span = Span::new(
+                        shift(prev.span.start, delta),
+                        shift(prev.span.end, delta),
+                        shift(prev.span.line, lines),
+                        shift(prev.span.column, columns),
+                    );
+                    Token::with_span(prev.tokentype.clone(), prev.literal.clone(), span)
+                }));
+                return Relexed {
+                    tokens: result,
+                    changed,
+                };
+            }
+        }
+        result.push(tok);
+    }
+    Relexed {
+        changed: first..result.len(),
+        tokens: result,
+    }
+}
+
+/// Check if the previous token `old` is the same as the new one once moved by the edit
+fn is_same_token(old: &Token, new: &Token, delta: isize) -> bool {
+    shift(old.span.start, delta) == new.span.start
+        && shift(old.span.end, delta) == new.span.end
+        && old.tokentype == new.tokentype
+        && old.literal == new.literal
+}
+
+fn shift(value: usize, delta: isize) -> usize {
+    if delta < 0 {
+        value.saturating_sub(delta.unsigned_abs())
+    } else {
+        value + delta as usize
+    }
+}
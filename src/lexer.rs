@@ -1,163 +1,763 @@
 #![warn(missing_docs)]
-use crate::token::{Token, TokenType};
-use crate::TokenBuilder;
+//! Lexer of the Monkey language, turns the source code into a stream of tokens.
+use crate::error::{LexError, LexWarning, LiteralKind};
+use crate::identifier::{Edition, TokenBuilder};
+use crate::token::{Span, Token, TokenType};
+use std::borrow::Cow;
+use std::io::BufRead;
+use std::iter::FusedIterator;
+use unicode_xid::UnicodeXID;
 
-// Struct used to read and lex the entire code
-pub struct Lexer {
-    input: Vec<u8>,
+/// Struct used to read and lex the entire code
+/// The input is read char by char, positions are byte offsets in the input.
+/// When the code comes from a reader, `input` only holds the part of the code that has not been
+/// lexed yet, `offset` being the position of its first byte.
+/// The lexer can either own its input or borrow it for the `'src` lifetime.
+pub struct Lexer<'src> {
+    input: Cow<'src, str>,
+    reader: Option<Box<dyn BufRead + Send + 'src>>,
+    offset: usize,
+    done: bool,
+    capture: bool,
     position: usize,
     read_position: usize,
-    ch: u8,
+    ch: char,
+    line: usize,
+    column: usize,
+    strict: bool,
+    keywords: TokenBuilder,
+    errors: Vec<LexError>,
+    warnings: Vec<LexWarning>,
 }
-impl Lexer {
+impl<'src> Lexer<'src> {
     /// Builder of the lexer - Returns a Lexer struct
-    /// * `input` - Code that neeeds to be parsed.
-    pub fn new(input: String) -> Lexer {
+    /// * `input` - Code that neeeds to be parsed, either a `String` or a `&str`.
+    pub fn new<S: Into<Cow<'src, str>>>(input: S) -> Lexer<'src> {
+        Lexer::with_keywords(input, TokenBuilder::default())
+    }
+
+    /// Builder of a lexer recognising the keywords registered in `keywords` instead of the
+    /// default ones.
+    pub fn with_keywords<S: Into<Cow<'src, str>>>(input: S, keywords: TokenBuilder) -> Lexer<'src> {
         let mut mylexer = Lexer {
-            input: input.as_bytes().to_vec(),
+            input: input.into(),
+            reader: None,
+            offset: 0,
+            done: false,
+            capture: true,
             position: 0,
             read_position: 0,
-            ch: 0,
+            ch: '\0',
+            line: 1,
+            column: 0,
+            strict: false,
+            keywords,
+            errors: Vec::new(),
+            warnings: Vec::new(),
         };
         mylexer.read_char();
         mylexer
     }
 
+    /// Builder of a lexer reading the code from `reader` as the tokens are requested, instead of
+    /// loading the whole code in memory first. The reader must be `Send` so that the lexer can
+    /// be moved to another thread.
+    pub fn from_reader<R: BufRead + Send + 'src>(reader: R) -> Lexer<'src> {
+        let mut mylexer = Lexer::new(String::new());
+        mylexer.reader = Some(Box::new(reader));
+        mylexer.column = 0;
+        mylexer.read_char();
+        mylexer
+    }
+
+    /// Returns the input that has not been read yet
+    fn remaining_input(&self) -> &str {
+        &self.input[self.read_position - self.offset..]
+    }
+
+    /// Makes sure that at least `chars` chars are available after the reading position, reading
+    /// lines from the reader if needed. Does nothing when the whole code is already in memory.
+    fn fill_input(&mut self, chars: usize) {
+        while let Some(reader) = self.reader.as_mut() {
+            // A char is at most 4 bytes long
+            if self.input.len() + self.offset >= self.read_position + 4 * chars {
+                return;
+            }
+            let mut line = String::new();
+            match reader.read_line(&mut line) {
+                Ok(0) => self.reader = None,
+                Ok(_) => self.input.to_mut().push_str(&line),
+                Err(error) => {
+                    let span = Span::new(
+                        self.read_position,
+                        self.read_position,
+                        self.line,
+                        self.column,
+                    );
+                    self.add_error(LexError::Io {
+                        message: error.to_string(),
+                        span,
+                    });
+                    self.reader = None;
+                }
+            }
+        }
+    }
+
+    /// Forget the part of the input that has already been lexed. Only done when reading from a
+    /// reader, and once half of the buffer is consumed to avoid moving the input for each token.
+    fn discard_read_input(&mut self) {
+        let consumed = self.position - self.offset;
+        if self.reader.is_some() && consumed > 0 && consumed >= self.input.len() / 2 {
+            self.input.to_mut().drain(..consumed);
+            self.offset = self.position;
+        }
+    }
+
     /// Read the next char and advance the reading position
+    /// Line and column are updated so that they always point to the current char.
     pub fn read_char(&mut self) {
-        if let Some(&ch) = self.input.get(self.read_position) {
-            self.ch = ch;
+        if self.ch == '\n' {
+            self.line += 1;
+            self.column = 1;
         } else {
-            self.ch = 0;
+            self.column += 1;
         }
         self.position = self.read_position;
-        self.read_position += 1;
+        self.fill_input(1);
+        if let Some(ch) = self.remaining_input().chars().next() {
+            self.ch = ch;
+            self.read_position += ch.len_utf8();
+        } else {
+            self.ch = '\0';
+        }
     }
 
     /// Read the next char but does not advance the reading position. Usefull to recognise 2 or 3
     /// characters keywords (e.g.  ==, != )
-    pub fn peek_char(&mut self) -> u8 {
-        if let Some(&next_char) = self.input.get(self.read_position) {
-            next_char
-        } else {
-            0
+    pub fn peek_char(&mut self) -> char {
+        self.peek_nth_char(0)
+    }
+
+    /// Same as peek_char but looks `n` characters after the next one.
+    fn peek_nth_char(&mut self, n: usize) -> char {
+        self.fill_input(n + 1);
+        self.remaining_input().chars().nth(n).unwrap_or('\0')
+    }
+
+    /// Move the lexer to the byte `position` of the input, which must be the start of a token
+    /// located at `line` and `column`. A leading pragma is read first so that the edition it
+    /// selects still applies. Only used on lexers whose whole input is in memory.
+    pub(crate) fn seek(&mut self, position: usize, line: usize, column: usize) {
+        if self.is_pragma() {
+            self.read_pragma();
         }
+        self.ch = '\0';
+        self.read_position = position;
+        self.line = line;
+        self.column = column - 1;
+        self.read_char();
+    }
+
+    /// When `capture` is false, the lexer does not copy the text of the tokens: literals are left
+    /// empty as well as identifiers, doc comments and strings without escape sequences.
+    /// Used by the `BorrowedLexer` which takes them from its input instead.
+    pub(crate) fn set_capture(&mut self, capture: bool) {
+        self.capture = capture;
+    }
+
+    /// In strict mode, control characters other than tabs, line breaks and form feeds are
+    /// reported as errors instead of being silently skipped as whitespaces.
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Check wether errors were met while lexing the input
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Returns the errors met so far while lexing the input
+    pub fn get_errors(&self) -> Vec<LexError> {
+        self.errors.clone()
+    }
+
+    fn add_error(&mut self, error: LexError) {
+        self.errors.push(error);
+    }
+
+    /// Check wether warnings were met while lexing the input
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    /// Returns the warnings met so far while lexing the input
+    pub fn get_warnings(&self) -> Vec<LexWarning> {
+        self.warnings.clone()
+    }
+
+    /// Select the edition of the language, which is otherwise given by a leading `#!edition`
+    /// pragma
+    pub fn set_edition(&mut self, edition: Edition) {
+        self.keywords.set_edition(edition);
     }
 
     /// Processes the next char(s) and return the next token
     pub fn next_token(&mut self) -> Token {
         let tok_type: TokenType;
-        let mut lit: String = String::from_utf8(vec![self.ch]).unwrap();
-        self.skip_whitespace();
+        self.discard_read_input();
+        loop {
+            if self.is_pragma() {
+                self.read_pragma();
+            }
+            self.skip_whitespace();
+            if self.is_doc_comment() {
+                return self.read_doc_comment();
+            }
+            if !self.skip_comment() {
+                break;
+            }
+        }
+        let start = self.position;
+        let (line, column) = (self.line, self.column);
         match self.ch {
-            0x2b => tok_type = TokenType::PLUS,
-            0x2c => tok_type = TokenType::COMMA,
-            0x2d => tok_type = TokenType::MINUS,
-            0x2a => tok_type = TokenType::ASTERISK,
-            0x2f => tok_type = TokenType::SLASH,
-            0x21 => {
-                if self.peek_char() == 0x3d {
+            '+' => tok_type = self.two_chars_token('=', TokenType::PLUSASSIGN, TokenType::PLUS),
+            ',' => tok_type = TokenType::COMMA,
+            '-' => tok_type = self.two_chars_token('=', TokenType::MINUSASSIGN, TokenType::MINUS),
+            '*' => {
+                if self.peek_char() == '=' {
+                    tok_type = TokenType::ASTERISKASSIGN;
+                    self.read_char();
+                } else {
+                    tok_type = self.two_chars_token('*', TokenType::POWER, TokenType::ASTERISK);
+                }
+            }
+            '/' => tok_type = self.two_chars_token('=', TokenType::SLASHASSIGN, TokenType::SLASH),
+            '%' => tok_type = TokenType::PERCENT,
+            '^' => tok_type = TokenType::CARET,
+            '&' => tok_type = self.two_chars_token('&', TokenType::AND, TokenType::AMPERSAND),
+            '|' => tok_type = self.two_chars_token('|', TokenType::OR, TokenType::PIPE),
+            '!' => {
+                if self.peek_char() == '=' {
                     tok_type = TokenType::NOTEQUAL;
                     self.read_char();
                 } else {
                     tok_type = TokenType::BANG;
                 }
             }
-            0x3d => {
-                if self.peek_char() == 0x3d {
+            '=' => {
+                if self.peek_char() == '=' {
                     tok_type = TokenType::EQUAL;
                     self.read_char(); //Bien penser à avancer car il s'agit d'un token sur deux chars
                 } else {
                     tok_type = TokenType::ASSIGN;
                 }
             }
-            0x3b => tok_type = TokenType::SEMICOLON,
-            0x28 => tok_type = TokenType::LPAREN,
-            0x29 => tok_type = TokenType::RPAREN,
-            0x7b => tok_type = TokenType::LBRACE,
-            0x7d => tok_type = TokenType::RBRACE,
+            '.' => {
+                if self.peek_char() == '.' && self.peek_nth_char(1) == '.' {
+                    tok_type = TokenType::ELLIPSIS;
+                    self.read_char();
+                    self.read_char();
+                } else if self.peek_char() == '.' {
+                    self.read_char();
+                    tok_type = self.two_chars_token('=', TokenType::DOTDOTEQUAL, TokenType::DOTDOT);
+                } else {
+                    let span = Span::new(start, self.read_position, line, column);
+                    self.add_error(LexError::UnexpectedChar { found: '.', span });
+                    tok_type = TokenType::ILLEGAL;
+                }
+            }
+            ';' => tok_type = TokenType::SEMICOLON,
+            ':' => tok_type = TokenType::COLON,
+            '(' => tok_type = TokenType::LPAREN,
+            ')' => tok_type = TokenType::RPAREN,
+            '{' => tok_type = TokenType::LBRACE,
+            '}' => tok_type = TokenType::RBRACE,
+            '[' => tok_type = TokenType::LBRACKET,
+            ']' => tok_type = TokenType::RBRACKET,
 
-            0x3c => tok_type = TokenType::LT,
-            0x3e => tok_type = TokenType::GT,
-            0x0 => tok_type = TokenType::EOF,
+            '<' => {
+                if self.peek_char() == '=' {
+                    tok_type = TokenType::LTEQUAL;
+                    self.read_char();
+                } else if self.peek_char() == '<' {
+                    tok_type = TokenType::LSHIFT;
+                    self.read_char();
+                } else {
+                    tok_type = TokenType::LT;
+                }
+            }
+            '>' => {
+                if self.peek_char() == '=' {
+                    tok_type = TokenType::GTEQUAL;
+                    self.read_char();
+                } else if self.peek_char() == '>' {
+                    tok_type = TokenType::RSHIFT;
+                    self.read_char();
+                } else {
+                    tok_type = TokenType::GT;
+                }
+            }
+            '"' => return self.read_string(false),
+            '\0' => {
+                let span = Span::new(start, start, line, column);
+                return Token::with_span(TokenType::EOF, String::new(), span);
+            }
             _ => {
-                if is_valid_letter(self.ch) {
-                    lit = self.read_identifier();
-                    tok_type = token_from_identifier(lit.clone());
-                    return Token::new(tok_type, lit);
+                if self.ch == 'r' && self.peek_char() == '"' {
+                    self.read_char();
+                    return self.read_string(true);
+                } else if is_valid_letter(self.ch) {
+                    self.skip_identifier();
+                    let span = Span::new(start, self.position, line, column);
+                    let ident = self.text(&span);
+                    let warning = self.keywords.get_deprecation(ident).map(|keyword| {
+                        LexWarning::DeprecatedKeyword {
+                            found: ident.to_string(),
+                            keyword: keyword.to_string(),
+                            span,
+                        }
+                    });
+                    let tok_type = match self.keywords.get_keyword(ident) {
+                        Some(keyword) => keyword,
+                        None if self.capture => TokenType::IDENT(ident.to_string()),
+                        None => TokenType::IDENT(String::new()),
+                    };
+                    self.warnings.extend(warning);
+                    return Token::with_span(tok_type, self.literal(&span), span);
                 } else if is_valid_number(self.ch) {
-                    lit = self.read_number();
-                    tok_type = TokenType::INT(lit.parse().unwrap());
-                    return Token::new(tok_type, lit);
+                    self.skip_number();
+                    let span = Span::new(start, self.position, line, column);
+                    let tok_type = match number_type(self.text(&span), span) {
+                        Ok(tok_type) => tok_type,
+                        Err(error) => {
+                            self.add_error(error);
+                            TokenType::ILLEGAL
+                        }
+                    };
+                    return Token::with_span(tok_type, self.literal(&span), span);
                 } else {
+                    let span = Span::new(start, self.read_position, line, column);
+                    self.add_error(LexError::UnexpectedChar {
+                        found: self.ch,
+                        span,
+                    });
                     tok_type = TokenType::ILLEGAL;
                 }
             }
         }
 
         self.read_char();
-        Token::new(tok_type, lit)
+        let span = Span::new(start, self.position, line, column);
+        Token::with_span(tok_type, self.literal(&span), span)
+    }
+
+    /// Returns `double` and reads the next char if it is `second`, returns `single` otherwise.
+    /// Used for operators that can be followed by a second char (e.g. `*` and `**`).
+    fn two_chars_token(&mut self, second: char, double: TokenType, single: TokenType) -> TokenType {
+        if self.peek_char() == second {
+            self.read_char();
+            double
+        } else {
+            single
+        }
+    }
+
+    /// Returns the text of the input covered by the given span
+    fn text(&self, span: &Span) -> &str {
+        &self.input[span.start - self.offset..span.end - self.offset]
+    }
+
+    /// Returns the literal of a token covering the given span, empty if literals are not captured
+    fn literal(&self, span: &Span) -> String {
+        if self.capture {
+            self.text(span).to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Read a string literal delimited by double quotes. The current char is the opening quote
+    /// (the `r` prefix has already been read for raw strings, which do not process escapes).
+    /// The token type holds the decoded value while the literal keeps the source text.
+    fn read_string(&mut self, raw: bool) -> Token {
+        let start = if raw {
+            self.position - 1
+        } else {
+            self.position
+        };
+        let (line, column) = (self.line, self.column - raw as usize);
+        // The value is only copied once an escape sequence is met, the input is used otherwise
+        let mut value: Option<String> = None;
+        self.read_char();
+        let content_start = self.position;
+        while self.ch != '"' {
+            match self.ch {
+                '\0' => {
+                    let span = Span::new(start, self.position, line, column);
+                    self.add_error(LexError::UnterminatedLiteral {
+                        kind: LiteralKind::String,
+                        text: self.text(&span).to_string(),
+                        span,
+                    });
+                    return Token::with_span(TokenType::ILLEGAL, self.literal(&span), span);
+                }
+                '\\' if !raw => {
+                    let content = Span::new(content_start, self.position, line, column);
+                    let decoded = value.get_or_insert_with(|| self.text(&content).to_string());
+                    self.read_escape(decoded);
+                }
+                ch => {
+                    if let Some(decoded) = value.as_mut() {
+                        decoded.push(ch);
+                    }
+                    self.read_char();
+                }
+            }
+        }
+        let content = Span::new(content_start, self.position, line, column);
+        let value = match value {
+            Some(decoded) => decoded,
+            None if self.capture => self.text(&content).to_string(),
+            None => String::new(),
+        };
+        self.read_char();
+        let span = Span::new(start, self.position, line, column);
+        Token::with_span(TokenType::STRING(value), self.literal(&span), span)
+    }
+
+    /// Decode the escape sequence starting at the current backslash and append it to `value`.
+    /// Unknown escapes are reported and kept as is in the string.
+    fn read_escape(&mut self, value: &mut String) {
+        let (start, line, column) = (self.position, self.line, self.column);
+        self.read_char();
+        let decoded = match self.ch {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            'u' => return self.read_unicode_escape(value, Span::new(start, start, line, column)),
+            _ => None,
+        };
+        match decoded {
+            Some(ch) => value.push(ch),
+            None => {
+                let span = Span::new(start, self.read_position, line, column);
+                self.add_error(LexError::InvalidEscape {
+                    text: self.text(&span).to_string(),
+                    span,
+                });
+                value.push('\\');
+                if self.ch == '\0' {
+                    return;
+                }
+                value.push(self.ch);
+            }
+        }
+        self.read_char();
+    }
+
+    /// Decode a `\u{...}` escape, the current char being the `u`.
+    /// `span` gives the location of the backslash.
+    fn read_unicode_escape(&mut self, value: &mut String, mut span: Span) {
+        let mut escape = String::from("\\u");
+        let mut code_point = String::new();
+        if self.peek_char() == '{' {
+            self.read_char();
+            escape.push(self.ch);
+            while self.peek_char().is_ascii_hexdigit() && code_point.len() < 6 {
+                self.read_char();
+                code_point.push(self.ch);
+            }
+            escape.push_str(&code_point);
+            if self.peek_char() == '}' {
+                self.read_char();
+                escape.push(self.ch);
+                let decoded = u32::from_str_radix(&code_point, 16)
+                    .ok()
+                    .and_then(char::from_u32);
+                if let Some(ch) = decoded {
+                    value.push(ch);
+                    self.read_char();
+                    return;
+                }
+            }
+        }
+        span.end = self.read_position;
+        self.add_error(LexError::InvalidEscape {
+            text: escape.clone(),
+            span,
+        });
+        value.push_str(&escape);
+        self.read_char();
     }
 
     /// Loop through the input until a non number character is found
-    /// Currently only support numbers composed by chars ranging from 0 to 9
-    /// That is only integer support for now
-    pub fn read_number(&mut self) -> String {
-        let mut my_number: Vec<u8> = vec![];
+    /// Numbers are made of chars ranging from 0 to 9, optionally followed by a fractional part
+    /// (`3.14`) and an exponent (`1e-9`), in which case they are floats.
+    /// Integers can also be written in hexadecimal (`0xff`), octal (`0o17`) or binary (`0b101`)
+    /// and digits can be separated by underscores (`1_000_000`).
+    fn skip_number(&mut self) {
+        if self.ch == '0' && matches!(self.peek_char(), 'x' | 'o' | 'b') {
+            self.read_char();
+            self.read_char();
+            // Invalid digits are read as well so that they can be reported
+            while self.ch.is_ascii_alphanumeric() || self.ch == '_' {
+                self.read_char();
+            }
+            return;
+        }
+        self.skip_digits();
+        if self.ch == '.' && is_valid_number(self.peek_char()) {
+            self.read_char();
+            self.skip_digits();
+        }
+        if self.ch == 'e' || self.ch == 'E' {
+            let sign = self.peek_char();
+            if is_valid_number(sign)
+                || ((sign == '+' || sign == '-') && is_valid_number(self.peek_nth_char(1)))
+            {
+                self.read_char();
+                self.read_char();
+                self.skip_digits();
+            }
+        }
+    }
+
+    /// Reads chars as long as they are digits or separators
+    fn skip_digits(&mut self) {
         while is_valid_number(self.ch) {
-            my_number.push(self.ch);
             self.read_char();
+            while self.ch == '_' {
+                self.read_char();
+            }
         }
-        String::from_utf8(my_number).unwrap()
     }
 
-    /// Read an identifier from the current position until something else than a character is found
-    pub fn read_identifier(&mut self) -> String {
-        let mut char_bytes: Vec<u8> = vec![];
-        while is_valid_letter(self.ch) {
-            char_bytes.push(self.ch);
+    /// Read an identifier from the current position until something else than a character or a
+    /// digit is found
+    fn skip_identifier(&mut self) {
+        while is_valid_identifier_char(self.ch) {
             self.read_char();
         }
-        return String::from_utf8(char_bytes).unwrap();
+    }
+
+    /// Check if the current position starts a `#!` pragma, which is only allowed as the very
+    /// first line of the input.
+    fn is_pragma(&mut self) -> bool {
+        self.line == 1 && self.column == 1 && self.ch == '#' && self.peek_char() == '!'
+    }
+
+    /// Read a `#!edition <number>` pragma and select the matching edition. The line break is
+    /// consumed as part of the pragma.
+    fn read_pragma(&mut self) {
+        let start = self.position;
+        let (line, column) = (self.line, self.column);
+        while self.ch != '\n' && self.ch != '\0' {
+            self.read_char();
+        }
+        let span = Span::new(start, self.position, line, column);
+        let text = self.text(&span).trim_end();
+        let mut words = text[2..].split_whitespace();
+        let edition = match (words.next(), words.next(), words.next()) {
+            (Some("edition"), Some(number), None) => {
+                number.parse().ok().and_then(Edition::from_number)
+            }
+            _ => None,
+        };
+        match edition {
+            Some(edition) => self.set_edition(edition),
+            None => self.add_error(LexError::InvalidPragma {
+                text: text.to_string(),
+                span,
+            }),
+        }
+        if self.ch == '\n' {
+            self.read_char();
+        }
+    }
+
+    /// Check if the current position starts a doc comment: exactly three slashes.
+    fn is_doc_comment(&mut self) -> bool {
+        self.ch == '/'
+            && self.peek_nth_char(0) == '/'
+            && self.peek_nth_char(1) == '/'
+            && self.peek_nth_char(2) != '/'
+    }
+
+    /// Read a `///` doc comment until the end of the line. The line break is consumed as part
+    /// of the comment, the returned token holds the comment text without the slashes.
+    fn read_doc_comment(&mut self) -> Token {
+        let start = self.position;
+        let (line, column) = (self.line, self.column);
+        while self.ch != '\n' && self.ch != '\0' {
+            self.read_char();
+        }
+        let span = Span::new(start, self.position, line, column);
+        let doc = if self.capture {
+            doc_comment_text(self.text(&span)).to_string()
+        } else {
+            String::new()
+        };
+        let lit = self.literal(&span);
+        if self.ch == '\n' {
+            self.read_char();
+        }
+        Token::with_span(TokenType::DOC(doc), lit, span)
+    }
+
+    /// Skips a `//` line comment or a `/* */` block comment if one starts at the current
+    /// position. Block comments can be nested. Returns wether a comment was skipped.
+    fn skip_comment(&mut self) -> bool {
+        if self.ch != '/' {
+            return false;
+        }
+        match self.peek_char() {
+            '/' => {
+                while self.ch != '\n' && self.ch != '\0' {
+                    self.read_char();
+                }
+                if self.ch == '\n' {
+                    self.read_char();
+                }
+                true
+            }
+            '*' => {
+                let (start, line, column) = (self.position, self.line, self.column);
+                let mut depth = 0;
+                loop {
+                    if self.ch == '/' && self.peek_char() == '*' {
+                        depth += 1;
+                        self.read_char();
+                    } else if self.ch == '*' && self.peek_char() == '/' {
+                        depth -= 1;
+                        self.read_char();
+                    } else if self.ch == '\0' {
+                        let span = Span::new(start, self.position, line, column);
+                        self.add_error(LexError::UnterminatedLiteral {
+                            kind: LiteralKind::BlockComment,
+                            text: self.text(&span).to_string(),
+                            span,
+                        });
+                        return true;
+                    }
+                    self.read_char();
+                    if depth == 0 {
+                        return true;
+                    }
+                }
+            }
+            _ => false,
+        }
     }
 
     /// Reads chars as long as a whitespace is encountered.
+    /// Spaces, tabs, form feeds and both LF and CRLF line endings are whitespaces, lines being
+    /// counted by `read_char`. Other control characters are skipped as well, unless the lexer is
+    /// in strict mode where they are reported.
     pub fn skip_whitespace(&mut self) {
-        while self.ch == 0x20 {
+        loop {
+            match self.ch {
+                ' ' | '\t' | '\n' | '\r' | '\x0c' => {}
+                ch if ch.is_control() && ch != '\0' => {
+                    if self.strict {
+                        let span =
+                            Span::new(self.position, self.read_position, self.line, self.column);
+                        self.add_error(LexError::UnexpectedChar { found: ch, span });
+                    }
+                }
+                _ => return,
+            }
             self.read_char();
         }
     }
 }
 
-/// Check is a given character (Given as UTF8 byte) is a valid letter for identifier / function
-pub fn is_valid_letter(letter: u8) -> bool {
-    (letter >= 0x41 && letter <= 0x5a) || (letter >= 0x61 && letter <= 0x7a) || letter == 0x5f
+/// Iterating over the lexer gives every token of the code, the last one being the EOF token.
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if self.done {
+            return None;
+        }
+        let token = self.next_token();
+        self.done = token.tokentype == TokenType::EOF;
+        Some(token)
+    }
+}
+
+impl FusedIterator for Lexer<'_> {}
+
+/// Check is a given character is a valid first letter for identifier / function
+/// Any Unicode XID_Start character is accepted, as well as the underscore.
+pub fn is_valid_letter(letter: char) -> bool {
+    letter.is_xid_start() || letter == '_'
+}
+
+/// Check is a given character can be part of an identifier after its first letter
+/// Any Unicode XID_Continue character is accepted, which includes digits and the underscore.
+pub fn is_valid_identifier_char(letter: char) -> bool {
+    letter.is_xid_continue()
 }
 
 /// Checks if the current character is a valid number from the UTF-8 table.
-pub fn is_valid_number(letter: u8) -> bool {
-    letter >= 0x30 && letter <= 0x39
+pub fn is_valid_number(letter: char) -> bool {
+    letter.is_ascii_digit()
 }
 
-/// Create a token from a identifier given as parameter
-/// Token mades from identifier are what can be considered as keywords.
-pub fn token_from_identifier(ident: String) -> TokenType {
-    match ident.as_str() {
-        "fn" => TokenType::FUNCTION,
-        "FN" => TokenType::FUNCTION,
-        "let" => TokenType::LET,
-        "LET" => TokenType::LET,
-        "if" => TokenType::IF,
-        "IF" => TokenType::IF,
-        "else" => TokenType::ELSE,
-        "ELSE" => TokenType::ELSE,
-        "return" => TokenType::RETURN,
-        "RETURN" => TokenType::RETURN,
-        "TRUE" => TokenType::TRUE,
-        "true" => TokenType::TRUE,
-        "FALSE" => TokenType::FALSE,
-        "false" => TokenType::FALSE,
-
-        _ => TokenType::IDENT(ident.clone()),
+/// Returns the text of a `///` doc comment, without the slashes and the surrounding whitespaces
+pub fn doc_comment_text(comment: &str) -> &str {
+    comment.trim_start_matches('/').trim()
+}
+
+/// Compute the token type of a number literal read by `skip_number`, located at `span`.
+/// Returns an error when the literal does not fit an integer or contains invalid digits.
+pub fn number_type(lit: &str, span: Span) -> Result<TokenType, LexError> {
+    let (radix, digits) = match lit.get(..2) {
+        Some("0x") => (16, &lit[2..]),
+        Some("0o") => (8, &lit[2..]),
+        Some("0b") => (2, &lit[2..]),
+        _ => (10, lit),
+    };
+    if radix == 10 && digits.contains(['.', 'e', 'E']) {
+        let float: Cow<str> = if digits.contains('_') {
+            Cow::Owned(digits.replace('_', ""))
+        } else {
+            Cow::Borrowed(digits)
+        };
+        // skip_number only accepts well formed floats, this cannot fail
+        return Ok(TokenType::FLOAT(float.parse().unwrap_or(f64::NAN)));
+    }
+    let mut value: Option<usize> = None;
+    for digit in digits.chars().filter(|&c| c != '_') {
+        let digit = match digit.to_digit(radix) {
+            Some(digit) => digit as usize,
+            None => {
+                return Err(LexError::InvalidDigit {
+                    found: digit,
+                    radix,
+                    text: lit.to_string(),
+                    span,
+                })
+            }
+        };
+        value = match value.unwrap_or(0).checked_mul(radix as usize) {
+            Some(shifted) => shifted.checked_add(digit),
+            None => None,
+        };
+        if value.is_none() {
+            return Err(LexError::NumberOverflow {
+                text: lit.to_string(),
+                span,
+            });
+        }
+    }
+    match value {
+        Some(value) => Ok(TokenType::INT(value)),
+        None => Err(LexError::MissingDigits {
+            text: lit.to_string(),
+            span,
+        }),
     }
 }
@@ -1,16 +1,27 @@
 //! Monkey Langage Rust implementation
 //!
 //! This is an educationnal implementation and does not intend to replace the know Rust version.
+#![allow(non_snake_case)]
 
 pub mod ast;
+pub mod borrowed;
+pub mod error;
 pub mod identifier;
+pub mod incremental;
 pub mod lexer;
 pub mod parser;
 pub mod repl;
+pub mod symbol;
 pub mod token;
+pub mod trace;
 pub use ast::*;
+pub use borrowed::*;
+pub use error::*;
 pub use identifier::*;
+pub use incremental::*;
 pub use lexer::*;
 pub use parser::*;
 pub use repl::*;
+pub use symbol::*;
 pub use token::*;
+pub use trace::*;
@@ -1,118 +1,328 @@
 use std::collections::HashMap;
 
 use crate::ast::*;
+use crate::error::{ParseError, ParseWarning};
+use crate::identifier::TokenBuilder;
 use crate::lexer::*;
 use crate::token::*;
+use crate::trace::ParserTracer;
 use std::mem::*;
 
-pub type PrefixParseFn = fn(Token) -> Option<Expr>;
-pub type InfixParseFn = fn(Expr) -> Option<Expr>;
+/// Parser of an expression starting with a prefix token, called with this token as current token.
+/// It must leave the last token of the expression as current token.
+pub type PrefixParseFn = fn(&mut Parser) -> Option<Expr>;
+/// Parser of an infix expression, called with the operator as current token and the expression
+/// on its left. It must leave the last token of the expression as current token.
+pub type InfixParseFn = fn(&mut Parser, Expr) -> Option<Expr>;
+/// Parser of the statement introduced by a custom keyword, called with the keyword as current
+/// token. It must leave the last token of the statement as current token.
+pub type StatementParseFn = fn(&mut Parser) -> Option<Stmt>;
+
+/// Maximum number of nested parse functions, deeper expressions and blocks are reported as errors
+/// instead of overflowing the stack
+const MAX_DEPTH: usize = 256;
+
+/// Key of the parse tables. Tokens holding a value share the same entry (e.g. every `INT`),
+/// except custom keywords which each have their own, so that they can be used as operators.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct TokenKey(Discriminant<TokenType>, Option<String>);
+
+impl From<&TokenType> for TokenKey {
+    fn from(tokentype: &TokenType) -> TokenKey {
+        match tokentype {
+            TokenType::KEYWORD(keyword) => TokenKey(discriminant(tokentype), Some(keyword.clone())),
+            _ => TokenKey(discriminant(tokentype), None),
+        }
+    }
+}
 
 pub struct Parser {
-    lexer: Lexer,
+    lexer: Lexer<'static>,
     cur_token: Token,
     peek_token: Token,
-    errors: Vec<String>,
-    prefix_parsers: HashMap<Discriminant<TokenType>, PrefixParseFn>,
-    infix_parsers: HashMap<Discriminant<TokenType>, InfixParseFn>,
-    precedences: HashMap<Discriminant<TokenType>, Precedence>,
+    errors: Vec<ParseError>,
+    warnings: Vec<ParseWarning>,
+    prefix_parsers: HashMap<TokenKey, PrefixParseFn>,
+    infix_parsers: HashMap<TokenKey, InfixParseFn>,
+    precedences: HashMap<TokenKey, (Precedence, Associativity)>,
+    statement_parsers: HashMap<String, StatementParseFn>,
+    tracer: Option<Box<dyn ParserTracer + Send>>,
+    depth: usize,
+    // Set once the nesting limit is reported, until the end of the top level statement
+    too_deep: bool,
+    // Number of loops enclosing the current token in the body of the current function
+    loop_depth: usize,
 }
 impl Parser {
     pub fn new(input: String) -> Parser {
+        Parser::with_keywords(input, TokenBuilder::default())
+    }
+
+    /// Builder of a parser for a dialect whose keywords are registered in `keywords`.
+    /// Statements introduced by custom keywords are parsed by the functions registered with
+    /// `add_statement_parser`.
+    pub fn with_keywords(input: String, keywords: TokenBuilder) -> Parser {
         let mut my_parser = Parser {
-            lexer: Lexer::new(input),
+            lexer: Lexer::with_keywords(input, keywords),
             cur_token: Token::new(TokenType::ILLEGAL, "".to_string()),
             peek_token: Token::new(TokenType::ILLEGAL, "".to_string()),
             errors: Vec::new(),
+            warnings: Vec::new(),
             prefix_parsers: HashMap::new(),
             infix_parsers: HashMap::new(),
             precedences: HashMap::new(),
+            statement_parsers: HashMap::new(),
+            tracer: None,
+            depth: 0,
+            too_deep: false,
+            loop_depth: 0,
         };
-        my_parser.add_prefix_parser(
-            discriminant(&TokenType::IDENT('x'.to_string())),
-            parse_identifier,
+        my_parser.add_prefix_parser(&TokenType::IDENT(String::new()), parse_identifier);
+        my_parser.add_prefix_parser(&TokenType::INT(0), parse_integer);
+        my_parser.add_prefix_parser(&TokenType::FLOAT(0.0), parse_float);
+        my_parser.add_prefix_parser(&TokenType::STRING(String::new()), parse_string);
+        my_parser.add_prefix_parser(&TokenType::TRUE, parse_boolean);
+        my_parser.add_prefix_parser(&TokenType::FALSE, parse_boolean);
+        my_parser.add_prefix_parser(&TokenType::BANG, Parser::parse_prefix_expression);
+        my_parser.add_prefix_parser(&TokenType::MINUS, Parser::parse_prefix_expression);
+        my_parser.add_prefix_parser(&TokenType::LPAREN, Parser::parse_grouped_expression);
+        my_parser.add_prefix_parser(&TokenType::IF, Parser::parse_if_expression);
+        my_parser.add_prefix_parser(&TokenType::FUNCTION, Parser::parse_function_literal);
+        my_parser.add_prefix_parser(&TokenType::LBRACKET, Parser::parse_array_literal);
+        my_parser.add_prefix_parser(&TokenType::LBRACE, Parser::parse_hash_literal);
+
+        let operators = [
+            (TokenType::OR, Precedence::OR),
+            (TokenType::AND, Precedence::AND),
+            (TokenType::EQUAL, Precedence::EQUALS),
+            (TokenType::NOTEQUAL, Precedence::EQUALS),
+            (TokenType::LT, Precedence::LESSGREATER),
+            (TokenType::GT, Precedence::LESSGREATER),
+            (TokenType::LTEQUAL, Precedence::LESSGREATER),
+            (TokenType::GTEQUAL, Precedence::LESSGREATER),
+            (TokenType::PIPE, Precedence::BITOR),
+            (TokenType::CARET, Precedence::BITXOR),
+            (TokenType::AMPERSAND, Precedence::BITAND),
+            (TokenType::LSHIFT, Precedence::SHIFT),
+            (TokenType::RSHIFT, Precedence::SHIFT),
+            (TokenType::PLUS, Precedence::SUM),
+            (TokenType::MINUS, Precedence::SUM),
+            (TokenType::ASTERISK, Precedence::PRODUCT),
+            (TokenType::SLASH, Precedence::PRODUCT),
+            (TokenType::PERCENT, Precedence::PRODUCT),
+        ];
+        for (tokentype, precedence) in operators.iter() {
+            my_parser.add_infix_operator(
+                tokentype,
+                precedence.clone(),
+                Associativity::LEFT,
+                Parser::parse_infix_expression,
+            );
+        }
+        // a = b = c is a = (b = c)
+        let assignments = [
+            TokenType::ASSIGN,
+            TokenType::PLUSASSIGN,
+            TokenType::MINUSASSIGN,
+            TokenType::ASTERISKASSIGN,
+            TokenType::SLASHASSIGN,
+        ];
+        for tokentype in assignments.iter() {
+            my_parser.add_infix_operator(
+                tokentype,
+                Precedence::ASSIGN,
+                Associativity::RIGHT,
+                Parser::parse_assign_expression,
+            );
+        }
+        // Ranges are not associative, a..b..c is reported by parse_range_expression
+        for tokentype in [TokenType::DOTDOT, TokenType::DOTDOTEQUAL].iter() {
+            my_parser.add_infix_operator(
+                tokentype,
+                Precedence::RANGE,
+                Associativity::LEFT,
+                Parser::parse_range_expression,
+            );
+        }
+        // a ** b ** c is a ** (b ** c)
+        my_parser.add_infix_operator(
+            &TokenType::POWER,
+            Precedence::POWER,
+            Associativity::RIGHT,
+            Parser::parse_infix_expression,
+        );
+        my_parser.add_infix_operator(
+            &TokenType::LPAREN,
+            Precedence::CALL,
+            Associativity::LEFT,
+            Parser::parse_call_expression,
+        );
+        my_parser.add_infix_operator(
+            &TokenType::LBRACKET,
+            Precedence::INDEX,
+            Associativity::LEFT,
+            Parser::parse_index_expression,
         );
-        my_parser.add_prefix_parser(discriminant(&TokenType::INT(5)), parse_integer);
-        my_parser.add_prefix_parser(discriminant(&TokenType::TRUE), parse_boolean);
-        my_parser.add_prefix_parser(discriminant(&TokenType::FALSE), parse_boolean);
 
+        my_parser.next_token();
+        my_parser.next_token();
         my_parser
-            .precedences
-            .insert(discriminant(&TokenType::EQUAL), Precedence::EQUALS);
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::NOTEQUAL), Precedence::EQUALS);
+    }
 
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::LT), Precedence::LESSGREATER);
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::GT), Precedence::LESSGREATER);
+    fn precedence(&self, token: &Token) -> (Precedence, Associativity) {
+        self.precedences
+            .get(&TokenKey::from(&token.tokentype))
+            .cloned()
+            .unwrap_or((Precedence::LOWEST, Associativity::LEFT))
+    }
 
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::PLUS), Precedence::SUM);
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::MINUS), Precedence::SUM);
+    pub fn cur_precedence(&self) -> Precedence {
+        self.precedence(&self.cur_token).0
+    }
 
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::ASTERISK), Precedence::PRODUCT);
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::SLASH), Precedence::PRODUCT);
+    pub fn peek_precedence(&self) -> Precedence {
+        self.precedence(&self.peek_token).0
+    }
 
-        my_parser
-            .precedences
-            .insert(discriminant(&TokenType::LPAREN), Precedence::CALL);
+    // Check wether the peeked operator takes the expression parsed at `precedence` as its left
+    // member. A right associative operator also takes it at its own precedence.
+    fn peek_binds_tighter(&self, precedence: &Precedence) -> bool {
+        match self.precedence(&self.peek_token) {
+            (peek, Associativity::RIGHT) => *precedence <= peek && peek > Precedence::LOWEST,
+            (peek, Associativity::LEFT) => *precedence < peek,
+        }
+    }
 
-        my_parser.next_token();
-        my_parser.next_token();
-        my_parser
+    //Pratt parser functions
+    /// Register the parser of the expressions starting with a token of type `token_type`. Tokens
+    /// holding a value are matched whatever their value is, except custom keywords.
+    pub fn add_prefix_parser(&mut self, token_type: &TokenType, func: PrefixParseFn) {
+        self.prefix_parsers.insert(TokenKey::from(token_type), func);
     }
 
-    // TODO: Refactor this piece of code to have only one function that access the precedences
-    // hashMap
-    pub fn cur_precedence(&mut self) -> Precedence {
-        let token: Token = self.cur_token.clone();
-        if let Some(prec) = self.precedences.get(&discriminant(&token.tokentype)) {
-            prec.clone()
-        } else {
-            Precedence::LOWEST
+    /// Register the parser of the infix expressions whose operator is a token of type
+    /// `token_type`, with the precedence and associativity of the operator.
+    pub fn add_infix_operator(
+        &mut self,
+        token_type: &TokenType,
+        precedence: Precedence,
+        associativity: Associativity,
+        func: InfixParseFn,
+    ) {
+        let key = TokenKey::from(token_type);
+        self.precedences
+            .insert(key.clone(), (precedence, associativity));
+        self.infix_parsers.insert(key, func);
+    }
+
+    /// Register the parser of the statements introduced by the custom keyword `keyword`
+    pub fn add_statement_parser(&mut self, keyword: &str, func: StatementParseFn) {
+        self.statement_parsers.insert(keyword.to_string(), func);
+    }
+
+    /// Plug a tracer told about every parse function entered and exited. The tracer must be
+    /// `Send` so that the parser can be moved to another thread.
+    pub fn set_tracer(&mut self, tracer: Box<dyn ParserTracer + Send>) {
+        self.tracer = Some(tracer);
+    }
+
+    fn trace_enter(&mut self, function: &str) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.enter(self.depth, function, &self.cur_token);
         }
+        self.depth += 1;
     }
 
-    pub fn peek_precedence(&mut self) -> Precedence {
-        let token: Token = self.peek_token.clone();
-        if let Some(prec) = self.precedences.get(&discriminant(&token.tokentype)) {
-            prec.clone()
-        } else {
-            Precedence::LOWEST
+    fn trace_exit(&mut self, function: &str) {
+        self.depth -= 1;
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.exit(self.depth, function, &self.cur_token);
         }
     }
 
-    //Pratt parser functions
-    pub fn add_prefix_parser(&mut self, token_type: Discriminant<TokenType>, func: PrefixParseFn) {
-        self.prefix_parsers.insert(token_type, func);
+    // Report an error when parsing the current token would nest too many parse functions. The
+    // nested code is then skipped, and the errors met while leaving the nested parse functions
+    // are dropped so that the limit is only reported once.
+    fn is_too_deep(&mut self) -> bool {
+        if self.depth < MAX_DEPTH {
+            return false;
+        }
+        self.add_error(ParseError::NestingTooDeep {
+            span: self.cur_token.span,
+        });
+        self.too_deep = true;
+        self.skip_nested_code();
+        true
+    }
+
+    // Skip the tokens up to the one before the delimiter closing the enclosing delimiter, so that
+    // the enclosing parse functions end on it, or up to the end of the code. The skipped code is
+    // left as an illegal token, which the enclosing parse functions do not mistake for the end of
+    // a statement or a block.
+    fn skip_nested_code(&mut self) {
+        let mut nesting: usize = match self.cur_token.tokentype {
+            TokenType::LPAREN | TokenType::LBRACE | TokenType::LBRACKET => 1,
+            TokenType::RPAREN | TokenType::RBRACE | TokenType::RBRACKET => return,
+            _ => 0,
+        };
+        loop {
+            match self.peek_token.tokentype {
+                TokenType::LPAREN | TokenType::LBRACE | TokenType::LBRACKET => nesting += 1,
+                TokenType::RPAREN | TokenType::RBRACE | TokenType::RBRACKET if nesting == 0 => {
+                    break
+                }
+                TokenType::RPAREN | TokenType::RBRACE | TokenType::RBRACKET => nesting -= 1,
+                TokenType::EOF => break,
+                _ => {}
+            }
+            self.next_token();
+        }
+        self.cur_token.tokentype = TokenType::ILLEGAL;
+    }
+
+    pub fn get_cur_token(&self) -> &Token {
+        &self.cur_token
     }
 
-    pub fn add_infix_parser(&mut self, token_type: Discriminant<TokenType>, func: InfixParseFn) {
-        self.infix_parsers.insert(token_type, func);
+    pub fn get_peek_token(&self) -> &Token {
+        &self.peek_token
     }
 
     /// Check wether errors are stored in the parser
     pub fn has_errors(&self) -> bool {
-        !self.errors.is_empty()
+        !self.errors.is_empty() || self.lexer.has_errors()
+    }
+
+    pub fn add_error(&mut self, error: ParseError) {
+        // Errors caused by the code skipped after reaching the nesting limit are not reported
+        if !self.too_deep {
+            self.errors.push(error);
+        }
     }
 
-    pub fn add_error(&mut self, error: String) {
-        self.errors.push(error);
+    /// Returns the errors met by the lexer followed by the ones met while parsing
+    pub fn get_errors(&self) -> Vec<ParseError> {
+        let mut errors: Vec<ParseError> = self
+            .lexer
+            .get_errors()
+            .into_iter()
+            .map(ParseError::Lex)
+            .collect();
+        errors.extend(self.errors.iter().cloned());
+        errors
     }
 
-    pub fn get_errors(&self) -> Vec<String> {
-        self.errors.clone()
+    /// Returns the warnings met while lexing followed by the ones met while parsing, which do
+    /// not prevent the code from parsing
+    pub fn get_warnings(&self) -> Vec<ParseWarning> {
+        let mut warnings: Vec<ParseWarning> = self
+            .lexer
+            .get_warnings()
+            .into_iter()
+            .map(ParseWarning::Lex)
+            .collect();
+        warnings.extend(self.warnings.iter().cloned());
+        warnings
     }
 
     /// Read the next token et places it in the peek_token
@@ -128,8 +338,15 @@ impl Parser {
             if let Some(stmt) = self.parse_statement() {
                 myp.push(stmt);
             } else {
-                println!("No stmt returned...");
+                self.synchronize();
+                // The block the error was met in may end a statement (e.g. a function literal)
+                if self.cur_token_is(&TokenType::RBRACE)
+                    && self.peek_token_is(&TokenType::SEMICOLON)
+                {
+                    self.next_token();
+                }
             }
+            self.too_deep = false;
             self.next_token();
         }
 
@@ -137,210 +354,518 @@ impl Parser {
     }
 
     pub fn parse_statement(&mut self) -> Option<Stmt> {
-        match self.cur_token.tokentype {
+        // Blocks nest statements without going through parse_expression
+        if self.is_too_deep() {
+            return None;
+        }
+        self.trace_enter("parse_statement");
+        let stmt = match self.cur_token.tokentype {
             TokenType::LET => self.parse_let_statement(),
             TokenType::RETURN => self.parse_return_statement(),
+            TokenType::DOC(_) => self.parse_documented_statement(),
+            TokenType::KEYWORD(_) => self.parse_custom_statement(),
+            TokenType::LBRACE => self.parse_block_or_hash(),
+            TokenType::WHILE => self.parse_while_statement(),
+            TokenType::FOR => self.parse_for_statement(),
+            TokenType::BREAK => self.parse_loop_control_statement(Stmt::BREAK),
+            TokenType::CONTINUE => self.parse_loop_control_statement(Stmt::CONTINUE),
             _ => self.parse_expression_statement(),
+        };
+        self.trace_exit("parse_statement");
+        stmt
+    }
+
+    // Skip the tokens of a statement that could not be parsed, up to the `;` ending it or the
+    // `}` ending the enclosing block, so that parsing goes on with the next statement
+    fn synchronize(&mut self) {
+        while !self.cur_token_is(&TokenType::SEMICOLON)
+            && !self.cur_token_is(&TokenType::RBRACE)
+            && !self.cur_token_is(&TokenType::EOF)
+        {
+            self.next_token();
+        }
+    }
+
+    // Hand the statement over to the parser registered for the custom keyword
+    pub fn parse_custom_statement(&mut self) -> Option<Stmt> {
+        let func = match &self.cur_token.tokentype {
+            TokenType::KEYWORD(keyword) => self.statement_parsers.get(keyword).copied(),
+            _ => None,
+        };
+        let key = TokenKey::from(&self.cur_token.tokentype);
+        match func {
+            Some(func) => func(self),
+            // Keywords used as prefix operators start expression statements
+            None if self.prefix_parsers.contains_key(&key) => self.parse_expression_statement(),
+            None => {
+                self.add_error(ParseError::UnknownStatement {
+                    keyword: self.cur_token.literal.clone(),
+                    span: self.cur_token.span,
+                });
+                None
+            }
+        }
+    }
+
+    // Parse doc comments and attach them to the let statement that follows. Doc comments before
+    // any other statement are ignored with a warning.
+    pub fn parse_documented_statement(&mut self) -> Option<Stmt> {
+        let mut doc_lines: Vec<String> = Vec::new();
+        while let TokenType::DOC(line) = &self.cur_token.tokentype {
+            doc_lines.push(line.clone());
+            self.next_token();
+        }
+        if self.cur_token_is(&TokenType::LET) {
+            self.parse_let_statement()
+                .map(|stmt| Stmt::DOCUMENTED(doc_lines.join("\n"), Box::new(stmt)))
+        } else {
+            self.warnings.push(ParseWarning::DanglingDocComment {
+                found: self.cur_token.tokentype.clone(),
+                span: self.cur_token.span,
+            });
+            // Nothing follows the doc comments at the end of a block or of the code
+            if self.cur_token_is(&TokenType::RBRACE) || self.cur_token_is(&TokenType::EOF) {
+                None
+            } else {
+                self.parse_statement()
+            }
         }
     }
 
     pub fn parse_grouped_expression(&mut self) -> Option<Expr> {
+        self.trace_enter("parse_grouped_expression");
+        let open = self.cur_token.clone();
         self.next_token();
         let exp = self.parse_expression(Precedence::LOWEST);
-        if !self.expect_next_token(&TokenType::RPAREN) {
+        let exp = if !self.expect_closing_token(&TokenType::RPAREN, &open) {
             None
         } else {
             exp
-        }
+        };
+        self.trace_exit("parse_grouped_expression");
+        exp
     }
 
     pub fn parse_expression_statement(&mut self) -> Option<Stmt> {
         let expr_stmt = self.parse_expression(Precedence::LOWEST);
 
-        if self.cur_token_is(&TokenType::SEMICOLON) {
+        if self.peek_token_is(&TokenType::SEMICOLON) {
             self.next_token();
         }
 
-        expr_stmt.map(|e| Stmt::EXPRESSION(e))
+        expr_stmt.map(Stmt::EXPRESSION)
     }
 
-    pub fn parse_prefix_expression(&mut self, token: TokenType) -> Option<Expr> {
-        self.next_token();
-        if let Some(right_exp) = self.parse_expression(Precedence::PREFIX) {
-            match token {
-                TokenType::BANG => Some(Expr::BANG(Box::new(right_exp))),
-                TokenType::MINUS => Some(Expr::MINUS(Box::new(right_exp))),
-                _ => None,
-            }
-        } else {
-            None
+    // Parse a prefix operator and its operand: `!`, `-` or a custom keyword
+    pub fn parse_prefix_expression(&mut self) -> Option<Expr> {
+        let token = self.cur_token.tokentype.clone();
+        if !matches!(
+            token,
+            TokenType::BANG | TokenType::MINUS | TokenType::KEYWORD(_)
+        ) {
+            self.add_error(ParseError::UnknownOperator {
+                found: token,
+                span: self.cur_token.span,
+            });
+            return None;
         }
+        self.trace_enter("parse_prefix_expression");
+        self.next_token();
+        let expr = self
+            .parse_expression(Precedence::PREFIX)
+            .map(|right_exp| match token {
+                TokenType::KEYWORD(keyword) => Expr::PREFIX(keyword, Box::new(right_exp)),
+                TokenType::BANG => Expr::BANG(Box::new(right_exp)),
+                _ => Expr::MINUS(Box::new(right_exp)),
+            });
+        self.trace_exit("parse_prefix_expression");
+        expr
     }
 
     pub fn parse_block_statement(&mut self) -> Option<BlockStatement> {
-        let mut stmts = Vec::new();
+        self.trace_enter("parse_block_statement");
+        let open = self.cur_token.clone();
         self.next_token();
+        let block = self.parse_block_rest(open, Vec::new());
+        self.trace_exit("parse_block_statement");
+        block
+    }
+
+    // Parse the statements of the block opened by `open` up to its closing brace, the current
+    // token being the start of the next statement and `stmts` the statements already parsed
+    fn parse_block_rest(&mut self, open: Token, mut stmts: Vec<Stmt>) -> Option<BlockStatement> {
         while !self.cur_token_is(&TokenType::RBRACE) && !self.cur_token_is(&TokenType::EOF) {
             if let Some(statement) = self.parse_statement() {
                 stmts.push(statement);
+            } else {
+                self.synchronize();
+                // The closing brace of the block must not be skipped
+                if self.cur_token_is(&TokenType::RBRACE) {
+                    break;
+                }
             }
             self.next_token();
         }
-        if stmts.len() > 0 {
-            Some(BlockStatement(stmts))
-        } else {
+        if self.cur_token_is(&TokenType::EOF) {
+            self.add_error(ParseError::UnclosedDelimiter {
+                delimiter: open.tokentype,
+                span: open.span,
+            });
             None
+        } else {
+            Some(BlockStatement(stmts))
         }
     }
 
-    pub fn parse_if_expression(&mut self) -> Option<Expr> {
-        println!("IF.1 - Entering IF parsing function");
-        if !self.expect_next_token(&TokenType::LPAREN) {
-            println!("IF.2 - Exiting IF parsing, no LPAREN token found");
+    // At the start of a statement `{` opens either a block or a hash literal, told apart by the
+    // colon following the first key of a hash. `{}` is an empty hash.
+    pub fn parse_block_or_hash(&mut self) -> Option<Stmt> {
+        let stmt = self.parse_block_or_hash_body();
+        if stmt.is_some() && self.peek_token_is(&TokenType::SEMICOLON) {
+            self.next_token();
+        }
+        stmt
+    }
+
+    // Same as parse_block_or_hash, leaving the semicolon ending the statement unread
+    fn parse_block_or_hash_body(&mut self) -> Option<Stmt> {
+        if self.peek_token_is(&TokenType::RBRACE) {
+            return self.parse_expression(Precedence::LOWEST).map(Stmt::EXPRESSION);
+        }
+        if self.is_too_deep() {
             return None;
         }
+        self.trace_enter("parse_block_or_hash");
+        let open = self.cur_token.clone();
         self.next_token();
-        let condition = self.parse_expression(Precedence::LOWEST);
-        match condition {
-            Some(condition) => {
-                println!("IF.3 - Condition has been parsed.");
-                if !self.expect_next_token(&TokenType::RPAREN) {
-                    return None;
-                } else {
-                    if !self.expect_next_token(&TokenType::LBRACE) {
-                        return None;
-                    } else {
-                        let conseq = self.parse_block_statement();
-                        match conseq {
-                            Some(conseq) => {
-                                println!("Returning IF Expression");
-                                return Some(Expr::IF(
-                                    Box::new(condition),
-                                    conseq.clone(),
-                                    conseq.clone(),
-                                ));
-                            }
-                            None => return None,
-                        }
-                    }
+        let stmt = match self.parse_first_block_element() {
+            Some((Stmt::EXPRESSION(key), true)) if self.peek_token_is(&TokenType::COLON) => self
+                .parse_hash_value()
+                .and_then(|value| self.parse_hash_entries(open, vec![(key, value)]))
+                // The hash may be the left operand of an operator, e.g. {"a": 1}["a"]
+                .and_then(|hash| self.parse_infix_operators(hash, &Precedence::LOWEST))
+                .map(Stmt::EXPRESSION),
+            Some((stmt, semicolon_unread)) => {
+                if semicolon_unread && self.peek_token_is(&TokenType::SEMICOLON) {
+                    self.next_token();
+                }
+                self.next_token();
+                self.parse_block_rest(open, vec![stmt]).map(Stmt::BLOCK)
+            }
+            None => {
+                self.synchronize();
+                if !self.cur_token_is(&TokenType::RBRACE) {
+                    self.next_token();
                 }
+                self.parse_block_rest(open, Vec::new()).map(Stmt::BLOCK)
             }
-            None => return None,
+        };
+        self.trace_exit("parse_block_or_hash");
+        stmt
+    }
+
+    // Parse the first statement of a block, which is the first key of a hash if it is an
+    // expression followed by a colon. The flag tells whether the semicolon which may end the
+    // statement is still unread, only such an expression can be a key.
+    fn parse_first_block_element(&mut self) -> Option<(Stmt, bool)> {
+        let custom_statement = match &self.cur_token.tokentype {
+            TokenType::KEYWORD(keyword) => self.statement_parsers.contains_key(keyword),
+            _ => false,
+        };
+        let key = TokenKey::from(&self.cur_token.tokentype);
+        if self.cur_token_is(&TokenType::LBRACE) {
+            self.parse_block_or_hash_body().map(|stmt| (stmt, true))
+        } else if self.prefix_parsers.contains_key(&key) && !custom_statement {
+            self.parse_expression(Precedence::LOWEST)
+                .map(|expr| (Stmt::EXPRESSION(expr), true))
+        } else {
+            self.parse_statement().map(|stmt| (stmt, false))
         }
     }
 
-    pub fn is_infixable(&self, tok: &TokenType) -> bool {
-        matches!(
-            tok,
-            TokenType::EQUAL
-                | TokenType::NOTEQUAL
-                | TokenType::GT
-                | TokenType::LT
-                | TokenType::PLUS
-                | TokenType::MINUS
-                | TokenType::SLASH
-                | TokenType::ASTERISK
-                | TokenType::LPAREN
-        )
+    // Parse a hash literal: { <Expression> : <Expression> <CommaToken> ... }
+    pub fn parse_hash_literal(&mut self) -> Option<Expr> {
+        self.trace_enter("parse_hash_literal");
+        let open = self.cur_token.clone();
+        let hash = self.parse_hash_entries(open, Vec::new());
+        self.trace_exit("parse_hash_literal");
+        hash
     }
 
-    pub fn parse_expression(&mut self, precedence: Precedence) -> Option<Expr> {
-        let mut left_expr = match self.cur_token.tokentype.clone() {
-            TokenType::BANG | TokenType::MINUS => {
-                println!("157: Returning a BANG OR MINUS Expression as left member");
-                self.parse_prefix_expression(self.cur_token.tokentype.clone())
+    // Parse the entries of the hash opened by `open` up to its closing brace, `pairs` being the
+    // entries already parsed
+    fn parse_hash_entries(&mut self, open: Token, mut pairs: Vec<(Expr, Expr)>) -> Option<Expr> {
+        loop {
+            if !pairs.is_empty() {
+                if !self.peek_token_is(&TokenType::COMMA) {
+                    break;
+                }
+                self.next_token();
             }
-            TokenType::LPAREN => self.parse_grouped_expression(),
-            TokenType::IF => {
-                println!("Parsing IF expression");
-                self.parse_if_expression()
+            if self.peek_token_is(&TokenType::RBRACE) {
+                break;
             }
-            TokenType::FUNCTION => self.parse_function_literal(),
-            _ => {
-                println!(
-                    "Parsing token for left_expr    {:?}",
-                    &self.cur_token.clone().tokentype
-                );
-                let prefix = self
-                    .prefix_parsers
-                    .get(&discriminant(&self.cur_token.tokentype));
-                match prefix {
-                    Some(prefix_func) => prefix_func(self.cur_token.clone()),
-                    None => {
-                        println!(
-                            "No parsing function found for {:?}",
-                            self.cur_token.clone().tokentype
-                        );
-                        None
+            self.next_token();
+            let key = self.parse_expression(Precedence::LOWEST)?;
+            let value = self.parse_hash_value()?;
+            pairs.push((key, value));
+        }
+        if self.expect_closing_token(&TokenType::RBRACE, &open) {
+            Some(Expr::HASH(pairs))
+        } else {
+            None
+        }
+    }
+
+    // Parse the colon and the value following the key of a hash entry
+    fn parse_hash_value(&mut self) -> Option<Expr> {
+        if !self.expect_next_token(&TokenType::COLON) {
+            return None;
+        }
+        self.next_token();
+        self.parse_expression(Precedence::LOWEST)
+    }
+
+    // Parse an if expression, the parentheses around the condition being optional:
+    // if <condition> { <consequence> } [else { <alternative> } | else <if expression>]
+    pub fn parse_if_expression(&mut self) -> Option<Expr> {
+        self.trace_enter("parse_if_expression");
+        self.next_token();
+        let expr = match self.parse_expression(Precedence::LOWEST) {
+            Some(condition) if self.expect_next_token(&TokenType::LBRACE) => {
+                self.parse_block_statement().and_then(|conseq| {
+                    if !self.peek_token_is(&TokenType::ELSE) {
+                        return Some(Expr::IF(Box::new(condition), conseq, None));
                     }
-                }
+                    self.next_token();
+                    self.parse_alternative()
+                        .map(|alter| Expr::IF(Box::new(condition), conseq, Some(alter)))
+                })
             }
+            _ => None,
         };
+        self.trace_exit("parse_if_expression");
+        expr
+    }
 
-        if left_expr == None {
-            println!("No left expression, returning none...");
-            None
+    // Parse the alternative following an else token, an else if being stored as a block holding
+    // only the next if expression
+    pub fn parse_alternative(&mut self) -> Option<BlockStatement> {
+        if self.peek_token_is(&TokenType::IF) {
+            self.next_token();
+            self.parse_if_expression()
+                .map(|else_if| BlockStatement(vec![Stmt::EXPRESSION(else_if)]))
+        } else if self.expect_next_token(&TokenType::LBRACE) {
+            self.parse_block_statement()
         } else {
-            println!("9. Left expression found, looking for something more");
-            while !self.expect_next_token(&TokenType::SEMICOLON)
-                && precedence < self.peek_precedence()
-            {
-                let peek_token = &self.peek_token.tokentype.clone();
-
-                // TODO: This part should be revamped to use the vector of infix functions
-                if self.is_infixable(peek_token) {
-                    if self.peek_token_is(&TokenType::LPAREN){
-                        self.next_token();
-                       left_expr = self.parse_call_expression(left_expr.unwrap());
-                    }else{
-                    println!("10. Infixable Peek_token == {:?}", peek_token);
+            None
+        }
+    }
+
+    pub fn parse_expression(&mut self, precedence: Precedence) -> Option<Expr> {
+        if self.is_too_deep() {
+            return None;
+        }
+        self.trace_enter("parse_expression");
+        let prefix = self
+            .prefix_parsers
+            .get(&TokenKey::from(&self.cur_token.tokentype))
+            .copied();
+        let left_expr = match prefix {
+            Some(prefix_func) => prefix_func(self),
+            // The lexer already reported the error of an illegal token
+            None if self.cur_token_is(&TokenType::ILLEGAL) => None,
+            None => {
+                self.add_error(ParseError::MissingExpression {
+                    found: self.cur_token.tokentype.clone(),
+                    span: self.cur_token.span,
+                });
+                None
+            }
+        };
+        let expr = left_expr.and_then(|left| self.parse_infix_operators(left, &precedence));
+        self.trace_exit("parse_expression");
+        expr
+    }
+
+    // Parse the operators following the expression `left` as long as they bind tighter than
+    // `precedence`, the current token being the last token of `left`
+    fn parse_infix_operators(&mut self, mut left: Expr, precedence: &Precedence) -> Option<Expr> {
+        loop {
+            let infix = self
+                .infix_parsers
+                .get(&TokenKey::from(&self.peek_token.tokentype))
+                .copied();
+            match infix {
+                Some(infix_func) if self.peek_binds_tighter(precedence) => {
                     self.next_token();
-                    left_expr =
-                        self.parse_infix_expression(self.cur_token.clone(), left_expr.unwrap());
-                    }
-                } else {
-                    println!("11. Token is not Infixable, returning left_expr as is");
-                    return left_expr;
+                    left = infix_func(self, left)?;
                 }
+                _ => return Some(left),
             }
-            println!("12. Returning lext_expr as is. {:?}", left_expr);
-            left_expr
         }
     }
 
     pub fn parse_call_expression(&mut self,func_call: Expr) -> Option<Expr>{
-        let call_expr = Expr::CALL(Box::new(func_call), self.parse_function_params());
-        Some(call_expr)
+        self.trace_enter("parse_call_expression");
+        let call_expr = self
+            .parse_call_arguments()
+            .map(|args| Expr::CALL(Box::new(func_call), args));
+        self.trace_exit("parse_call_expression");
+        call_expr
     }
 
 
 
-    pub fn parse_infix_expression(&mut self, tok: Token, left_expression: Expr) -> Option<Expr> {
+    pub fn parse_infix_expression(&mut self, left_expression: Expr) -> Option<Expr> {
+        let tok = self.cur_token.clone();
+        let operator = Infix::from(tok.tokentype.clone());
+        if operator == Infix::ILLEGAL {
+            self.add_error(ParseError::UnknownOperator {
+                found: tok.tokentype,
+                span: tok.span,
+            });
+            return None;
+        }
         let precedence = self.cur_precedence();
+        self.trace_enter("parse_infix_expression");
         self.next_token();
-        println!(
-            "13. Parsing right expression, left is {:?}",
-            left_expression
-        );
-        let right_expr = self.parse_expression(precedence).unwrap();
-        Some(Expr::INFIX(
-            Box::new(left_expression),
-            tok.tokentype.into(),
-            Box::new(right_expr),
-        ))
+        let expr = self.parse_expression(precedence).map(|right_expr| {
+            Expr::INFIX(Box::new(left_expression), operator, Box::new(right_expr))
+        });
+        self.trace_exit("parse_infix_expression");
+        expr
+    }
+
+    // Parse an assignment, the current token being `=` or a compound assignment operator
+    pub fn parse_assign_expression(&mut self, target: Expr) -> Option<Expr> {
+        let tok = self.cur_token.clone();
+        if !matches!(target, Expr::IDENTIFIER(_) | Expr::INDEX(..)) {
+            self.add_error(ParseError::InvalidAssignmentTarget {
+                target,
+                span: tok.span,
+            });
+            return None;
+        }
+        let operator = match tok.tokentype {
+            TokenType::PLUSASSIGN => Some(Infix::PLUS),
+            TokenType::MINUSASSIGN => Some(Infix::MINUS),
+            TokenType::ASTERISKASSIGN => Some(Infix::MULTIPLY),
+            TokenType::SLASHASSIGN => Some(Infix::DIVIDE),
+            _ => None,
+        };
+        self.trace_enter("parse_assign_expression");
+        self.next_token();
+        let expr = self
+            .parse_expression(Precedence::ASSIGN)
+            .map(|value| Expr::ASSIGN(Box::new(target), operator, Box::new(value)));
+        self.trace_exit("parse_assign_expression");
+        expr
+    }
+
+    // Parse a range, the current token being `..` or `..=`
+    pub fn parse_range_expression(&mut self, start: Expr) -> Option<Expr> {
+        let inclusive = self.cur_token_is(&TokenType::DOTDOTEQUAL);
+        self.trace_enter("parse_range_expression");
+        self.next_token();
+        let expr = match self.parse_expression(Precedence::RANGE) {
+            Some(_)
+                if self.peek_token_is(&TokenType::DOTDOT)
+                    || self.peek_token_is(&TokenType::DOTDOTEQUAL) =>
+            {
+                self.add_error(ParseError::ChainedRange {
+                    span: self.peek_token.span,
+                });
+                None
+            }
+            end => end.map(|end| Expr::RANGE(Box::new(start), Box::new(end), inclusive)),
+        };
+        self.trace_exit("parse_range_expression");
+        expr
+    }
+
+    // Parse a while loop, the parentheses around the condition being optional:
+    // while <condition> { <body> }
+    pub fn parse_while_statement(&mut self) -> Option<Stmt> {
+        self.trace_enter("parse_while_statement");
+        self.next_token();
+        let stmt = match self.parse_expression(Precedence::LOWEST) {
+            Some(condition) if self.expect_next_token(&TokenType::LBRACE) => self
+                .parse_loop_body()
+                .map(|body| Stmt::WHILE(condition, body)),
+            _ => None,
+        };
+        self.trace_exit("parse_while_statement");
+        stmt
+    }
+
+    // Parse a for loop, the parentheses around the variable and the iterable being optional:
+    // for <Identifier> in <iterable> { <body> }
+    pub fn parse_for_statement(&mut self) -> Option<Stmt> {
+        self.trace_enter("parse_for_statement");
+        let open = if self.peek_token_is(&TokenType::LPAREN) {
+            self.next_token();
+            Some(self.cur_token.clone())
+        } else {
+            None
+        };
+        let mut stmt = None;
+        if self.expect_next_token(&TokenType::IDENT(String::new())) {
+            let var_name = Ident(self.cur_token.literal.clone());
+            if self.expect_next_token(&TokenType::IN) {
+                self.next_token();
+                let iterable = self.parse_expression(Precedence::LOWEST);
+                let closed = match &open {
+                    Some(open) => self.expect_closing_token(&TokenType::RPAREN, open),
+                    None => true,
+                };
+                stmt = match iterable {
+                    Some(iterable) if closed && self.expect_next_token(&TokenType::LBRACE) => self
+                        .parse_loop_body()
+                        .map(|body| Stmt::FOR(var_name, iterable, body)),
+                    _ => None,
+                };
+            }
+        }
+        self.trace_exit("parse_for_statement");
+        stmt
+    }
+
+    // Parse the body of a loop, in which break and continue statements are allowed
+    fn parse_loop_body(&mut self) -> Option<BlockStatement> {
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+        body
+    }
+
+    // Parse a break or continue statement, which is only valid in the body of a loop
+    pub fn parse_loop_control_statement(&mut self, stmt: Stmt) -> Option<Stmt> {
+        if self.loop_depth == 0 {
+            self.add_error(ParseError::OutsideLoop {
+                keyword: self.cur_token.literal.clone(),
+                span: self.cur_token.span,
+            });
+            return None;
+        }
+        if self.peek_token_is(&TokenType::SEMICOLON) {
+            self.next_token();
+        }
+        Some(stmt)
     }
 
     pub fn parse_return_statement(&mut self) -> Option<Stmt> {
-        let expr = self.parse_expression(Precedence::LOWEST).unwrap();
-        if !self.peek_token_is(&TokenType::SEMICOLON){
-           self.next_token();
-           }
+        self.next_token();
+        let expr = self.parse_expression(Precedence::LOWEST)?;
+        if self.peek_token_is(&TokenType::SEMICOLON) {
+            self.next_token();
+        }
         Some(Stmt::RETURN(expr))
     }
 
     // Parse a let statement (let x = 3; for instance)
     pub fn parse_let_statement(&mut self) -> Option<Stmt> {
-        if !self.expect_next_token(&TokenType::IDENT("x".to_string())) {
+        if !self.expect_next_token(&TokenType::IDENT(String::new())) {
             return None;
         }
         let var_name: String = self.cur_token.literal.clone();
@@ -367,66 +892,175 @@ impl Parser {
     }
 
     pub fn parse_function_literal(&mut self) -> Option<Expr> {
-        if !self.expect_next_token(&TokenType::LPAREN) {
+        self.trace_enter("parse_function_literal");
+        let expr = if !self.expect_next_token(&TokenType::LPAREN) {
             None
         } else {
-            let param_list = self.parse_function_params();
-            if !self.expect_next_token(&TokenType::LBRACE) {
-                println!(
-                    "2.1 - No LBRACE expected, instead found : {:?}",
-                    &self.peek_token.tokentype
-                );
-                None
-            } else {
-                let blck_stmt = self.parse_block_statement();
-                if let Some(block) = blck_stmt {
-                    Some(Expr::FUNC(param_list, block))
-                } else {
-                    None
+            match self.parse_function_parameters() {
+                Some(param_list) if self.expect_next_token(&TokenType::LBRACE) => {
+                    // The loops around the function do not enclose its body
+                    let loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+                    let body = self.parse_block_statement();
+                    self.loop_depth = loop_depth;
+                    body.map(|block| Expr::FUNC(param_list, block))
                 }
+                _ => None,
             }
+        };
+        self.trace_exit("parse_function_literal");
+        expr
+    }
+
+    // Parse the parameters of a function definition:
+    // <Identifier> [= <Expression>] <CommaToken> ... [...<Identifier>]
+    pub fn parse_function_parameters(&mut self) -> Option<Vec<Param>> {
+        self.trace_enter("parse_function_parameters");
+        let mut param_list: Vec<Param> = Vec::new();
+        let open = self.cur_token.clone();
+        let mut complete = true;
+        if self.peek_token_is(&TokenType::RPAREN) {
+            self.next_token();
+        } else {
+            loop {
+                self.next_token();
+                let span = self.cur_token.span;
+                match self.parse_param() {
+                    Some(param) => {
+                        if param_list.iter().any(|prev| prev.name == param.name) {
+                            self.add_error(ParseError::DuplicateParameter {
+                                name: param.name.to_string(),
+                                span,
+                            });
+                            complete = false;
+                        }
+                        param_list.push(param);
+                    }
+                    None => complete = false,
+                }
+                if !complete || !self.peek_token_is(&TokenType::COMMA) {
+                    break;
+                }
+                self.next_token();
+                // Like the other lists, parameters may end with a trailing comma
+                if self.peek_token_is(&TokenType::RPAREN) {
+                    break;
+                }
+                if let Some(rest) = param_list.last().filter(|param| param.rest) {
+                    self.add_error(ParseError::MisplacedRestParameter {
+                        name: rest.name.to_string(),
+                        span,
+                    });
+                    complete = false;
+                    break;
+                }
+            }
+            complete = complete && self.expect_closing_token(&TokenType::RPAREN, &open);
         }
+        self.trace_exit("parse_function_parameters");
+        if complete {
+            Some(param_list)
+        } else {
+            None
+        }
+    }
+
+    // Parse a single parameter of a function definition, starting at the current token
+    pub fn parse_param(&mut self) -> Option<Param> {
+        match &self.cur_token.tokentype {
+            TokenType::ELLIPSIS => {
+                if !self.expect_next_token(&TokenType::IDENT(String::new())) {
+                    return None;
+                }
+                let mut param = Param::new(Ident(self.cur_token.literal.clone()));
+                param.rest = true;
+                Some(param)
+            }
+            TokenType::IDENT(_) => {
+                let mut param = Param::new(Ident(self.cur_token.literal.clone()));
+                if self.peek_token_is(&TokenType::ASSIGN) {
+                    self.next_token();
+                    self.next_token();
+                    param.default = Some(self.parse_expression(Precedence::LOWEST)?);
+                }
+                Some(param)
+            }
+            found => {
+                self.add_error(ParseError::InvalidParameter {
+                    found: found.clone(),
+                    span: self.cur_token.span,
+                });
+                None
+            }
+        }
+    }
+
+    pub fn parse_call_arguments(&mut self) -> Option<Parameters> {
+        self.trace_enter("parse_call_arguments");
+        let params = self.parse_expression_list(&TokenType::RPAREN).map(Parameters);
+        self.trace_exit("parse_call_arguments");
+        params
     }
 
-    pub fn parse_function_params(&mut self) -> Parameters {
-        // <Identifier> <CommaToken> <Identifier>
-        println!("1.2 - Entering parameter parsing");
+    pub fn parse_array_literal(&mut self) -> Option<Expr> {
+        self.trace_enter("parse_array_literal");
+        let array = self
+            .parse_expression_list(&TokenType::RBRACKET)
+            .map(Expr::ARRAY);
+        self.trace_exit("parse_array_literal");
+        array
+    }
+
+    pub fn parse_index_expression(&mut self, left_expression: Expr) -> Option<Expr> {
+        self.trace_enter("parse_index_expression");
+        let open = self.cur_token.clone();
+        self.next_token();
+        let expr = match self.parse_expression(Precedence::LOWEST) {
+            Some(index) if self.expect_closing_token(&TokenType::RBRACKET, &open) => {
+                Some(Expr::INDEX(Box::new(left_expression), Box::new(index)))
+            }
+            _ => None,
+        };
+        self.trace_exit("parse_index_expression");
+        expr
+    }
+
+    // Parse a list of expressions separated by commas, the current token being the opening
+    // delimiter and `end` the closing one: <Expression> <CommaToken> <Expression>
+    pub fn parse_expression_list(&mut self, end: &TokenType) -> Option<Vec<Expr>> {
         let mut param_list: Vec<Expr> = Vec::new();
-        if !self.expect_next_token(&TokenType::RPAREN) {
+        let open = self.cur_token.clone();
+        if !self.peek_token_is(end) {
             self.next_token();
 
-            let myparam = self.parse_expression(Precedence::LOWEST);
-            if let Some(param) = myparam {
-                println!("1.1 - Found function args");
-                param_list.push(param);
-            } else {
-                return Parameters(param_list);
-            }
+            let mut complete = match self.parse_expression(Precedence::LOWEST) {
+                Some(param) => {
+                    param_list.push(param);
+                    true
+                }
+                None => false,
+            };
 
             // Careful here, we don't want to advance the token but we just want to peek at it.
-            while self.peek_token_is(&TokenType::COMMA) {
+            while complete && self.peek_token_is(&TokenType::COMMA) {
                 self.next_token();
+                // Like hash literals, lists may end with a trailing comma
+                if self.peek_token_is(end) {
+                    break;
+                }
                 self.next_token();
-                let myparam = self.parse_expression(Precedence::LOWEST);
-                if let Some(param) = myparam {
-                    println!("1.1 - Found function args");
-                    param_list.push(param);
-                } else {
-                    self.add_error(format!(
-                        "Could not parse arg in function / {:?}",
-                        &self.cur_token
-                    ));
-                    panic!()
+                match self.parse_expression(Precedence::LOWEST) {
+                    Some(param) => param_list.push(param),
+                    None => complete = false,
                 }
             }
-            if self.expect_next_token(&TokenType::RPAREN) {
-                println!("1.1 Exiting params parsing");
-                Parameters(param_list)
+            if complete && self.expect_closing_token(end, &open) {
+                Some(param_list)
             } else {
-                Parameters(Vec::new())
+                None
             }
         } else {
-            Parameters(param_list)
+            self.next_token();
+            Some(param_list)
         }
     }
     // Check the next expected token (peeked token) for a certain type of token
@@ -435,33 +1069,64 @@ impl Parser {
         if std::mem::discriminant(t) == std::mem::discriminant(&self.peek_token.tokentype) {
             self.next_token();
             true
+        } else if self.peek_token_is(&TokenType::ILLEGAL) {
+            // The lexer already reported the error of an illegal token
+            false
         } else {
-            let error = format!(
-                "Expected token {:?} was not found - Found {:?} instead",
-                t.clone(),
-                &self.peek_token.clone()
-            );
-            self.add_error(error);
+            self.add_error(ParseError::UnexpectedToken {
+                expected: t.clone(),
+                found: self.peek_token.tokentype.clone(),
+                span: self.peek_token.span,
+            });
+            false
+        }
+    }
+
+    // Same as expect_next_token for the token closing the delimiter `open`. Reaching the end of
+    // the code is reported at the opening delimiter, which is the one the user has to fix.
+    pub fn expect_closing_token(&mut self, t: &TokenType, open: &Token) -> bool {
+        if self.peek_token_is(&TokenType::EOF) {
+            self.add_error(ParseError::UnclosedDelimiter {
+                delimiter: open.tokentype.clone(),
+                span: open.span,
+            });
             false
+        } else {
+            self.expect_next_token(t)
         }
     }
 }
 
-pub fn parse_identifier(token: Token) -> Option<Expr> {
-    println!("Return an Identifier Expression");
-    Some(Expr::IDENTIFIER(Ident(token.literal)))
+pub fn parse_identifier(parser: &mut Parser) -> Option<Expr> {
+    Some(Expr::IDENTIFIER(Ident(parser.get_cur_token().literal.clone())))
 }
 
-pub fn parse_integer(token: Token) -> Option<Expr> {
-    if let TokenType::INT(int_value) = token.tokentype {
+pub fn parse_integer(parser: &mut Parser) -> Option<Expr> {
+    if let TokenType::INT(int_value) = parser.get_cur_token().tokentype {
         Some(Expr::INTEGER(int_value))
     } else {
         None
     }
 }
 
-pub fn parse_boolean(token: Token) -> Option<Expr> {
-    match token.tokentype {
+pub fn parse_float(parser: &mut Parser) -> Option<Expr> {
+    if let TokenType::FLOAT(float_value) = parser.get_cur_token().tokentype {
+        Some(Expr::FLOAT(float_value))
+    } else {
+        None
+    }
+}
+
+pub fn parse_string(parser: &mut Parser) -> Option<Expr> {
+    if let TokenType::STRING(value) = &parser.get_cur_token().tokentype {
+        Some(Expr::STRING(value.clone()))
+    } else {
+        None
+    }
+}
+
+pub fn parse_boolean(parser: &mut Parser) -> Option<Expr> {
+    match parser.get_cur_token().tokentype {
         TokenType::TRUE => Some(Expr::BOOLEAN(true)),
         TokenType::FALSE => Some(Expr::BOOLEAN(false)),
         _ => None,
@@ -1,12 +1,16 @@
 use crate::lexer::*;
-use crate::token::*;
 use std::io::Error;
-use std::io::{Read, Write};
 
 pub struct REPL {
     __stdin: std::io::Stdin,
 }
 
+impl Default for REPL {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl REPL {
     pub fn new() -> REPL {
         REPL {
@@ -18,20 +22,15 @@ impl REPL {
         let mut buffer = String::new();
 
         self.__stdin.read_line(&mut buffer)?;
-        while buffer != ":exit".to_string() {
-            let mut mylexer: Lexer = Lexer::new(buffer.clone());
-            let mut my_token: Token = Token::new(TokenType::EOF, "".to_string());
-            my_token = mylexer.next_token();
-            while my_token.tokentype != TokenType::EOF {
+        while buffer != ":exit" {
+            for my_token in Lexer::new(buffer.clone()) {
                 println!("{:?}", my_token);
-                my_token = mylexer.next_token();
             }
-            println!("{:?}", TokenType::EOF);
             self.print_line_from_repl(">>".to_string());
             self.__stdin.read_line(&mut buffer)?;
         }
 
-        return Ok(());
+        Ok(())
     }
 
     pub fn greeting(&self) {
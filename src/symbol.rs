@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+/// Identifier interned in a `SymbolTable`, comparing two symbols is comparing two integers.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Position of the symbol in the table that created it
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Stores each distinct identifier once. Names are borrowed from the source code, interning them
+/// does not copy them.
+#[derive(Debug, Default)]
+pub struct SymbolTable<'src> {
+    symbols: HashMap<&'src str, Symbol>,
+    names: Vec<&'src str>,
+}
+
+impl<'src> SymbolTable<'src> {
+    pub fn new() -> SymbolTable<'src> {
+        SymbolTable::default()
+    }
+
+    /// Returns the symbol of the given name, adding it to the table if needed
+    pub fn intern(&mut self, name: &'src str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(name) {
+            return *symbol;
+        }
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(name);
+        self.symbols.insert(name, symbol);
+        symbol
+    }
+
+    /// Returns the symbol of the given name if it has already been interned
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    /// Returns the name of a symbol, None if it does not come from this table
+    pub fn resolve(&self, symbol: Symbol) -> Option<&'src str> {
+        self.names.get(symbol.index()).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
@@ -1,10 +1,14 @@
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+use core::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum TokenType {
     ILLEGAL,
     EOF,
     ASSIGN,
     IDENT(String),
     INT(usize),
+    FLOAT(f64),
+    STRING(String),
     PLUS,
     COMMA,
     SEMICOLON,
@@ -27,12 +31,134 @@ pub enum TokenType {
     GT,
     EQUAL,
     NOTEQUAL,
+    LTEQUAL,
+    GTEQUAL,
+    PERCENT,
+    POWER,
+    AND,
+    OR,
+    AMPERSAND,
+    PIPE,
+    CARET,
+    LSHIFT,
+    RSHIFT,
+    DOC(String),
+    KEYWORD(String),
+    ELLIPSIS,
+    LBRACKET,
+    RBRACKET,
+    COLON,
+    PLUSASSIGN,
+    MINUSASSIGN,
+    ASTERISKASSIGN,
+    SLASHASSIGN,
+    WHILE,
+    BREAK,
+    CONTINUE,
+    FOR,
+    IN,
+    DOTDOT,
+    DOTDOTEQUAL,
+}
+
+impl TokenType {
+    /// Name of the kind of the token, without the value it may hold (e.g. `IDENT` for
+    /// `IDENT("x")`)
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            TokenType::ILLEGAL => "ILLEGAL",
+            TokenType::EOF => "EOF",
+            TokenType::ASSIGN => "ASSIGN",
+            TokenType::IDENT(_) => "IDENT",
+            TokenType::INT(_) => "INT",
+            TokenType::FLOAT(_) => "FLOAT",
+            TokenType::STRING(_) => "STRING",
+            TokenType::PLUS => "PLUS",
+            TokenType::COMMA => "COMMA",
+            TokenType::SEMICOLON => "SEMICOLON",
+            TokenType::LPAREN => "LPAREN",
+            TokenType::RPAREN => "RPAREN",
+            TokenType::LBRACE => "LBRACE",
+            TokenType::RBRACE => "RBRACE",
+            TokenType::FUNCTION => "FUNCTION",
+            TokenType::LET => "LET",
+            TokenType::MINUS => "MINUS",
+            TokenType::BANG => "BANG",
+            TokenType::ASTERISK => "ASTERISK",
+            TokenType::SLASH => "SLASH",
+            TokenType::RETURN => "RETURN",
+            TokenType::TRUE => "TRUE",
+            TokenType::FALSE => "FALSE",
+            TokenType::IF => "IF",
+            TokenType::ELSE => "ELSE",
+            TokenType::LT => "LT",
+            TokenType::GT => "GT",
+            TokenType::EQUAL => "EQUAL",
+            TokenType::NOTEQUAL => "NOTEQUAL",
+            TokenType::LTEQUAL => "LTEQUAL",
+            TokenType::GTEQUAL => "GTEQUAL",
+            TokenType::PERCENT => "PERCENT",
+            TokenType::POWER => "POWER",
+            TokenType::AND => "AND",
+            TokenType::OR => "OR",
+            TokenType::AMPERSAND => "AMPERSAND",
+            TokenType::PIPE => "PIPE",
+            TokenType::CARET => "CARET",
+            TokenType::LSHIFT => "LSHIFT",
+            TokenType::RSHIFT => "RSHIFT",
+            TokenType::DOC(_) => "DOC",
+            TokenType::KEYWORD(_) => "KEYWORD",
+            TokenType::ELLIPSIS => "ELLIPSIS",
+            TokenType::LBRACKET => "LBRACKET",
+            TokenType::RBRACKET => "RBRACKET",
+            TokenType::COLON => "COLON",
+            TokenType::PLUSASSIGN => "PLUSASSIGN",
+            TokenType::MINUSASSIGN => "MINUSASSIGN",
+            TokenType::ASTERISKASSIGN => "ASTERISKASSIGN",
+            TokenType::SLASHASSIGN => "SLASHASSIGN",
+            TokenType::WHILE => "WHILE",
+            TokenType::BREAK => "BREAK",
+            TokenType::CONTINUE => "CONTINUE",
+            TokenType::FOR => "FOR",
+            TokenType::IN => "IN",
+            TokenType::DOTDOT => "DOTDOT",
+            TokenType::DOTDOTEQUAL => "DOTDOTEQUAL",
+        }
+    }
+}
+
+/// Location of a token in the source code.
+/// Offsets are byte offsets in the input, line and column both start at 1.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Span {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub tokentype: TokenType,
     pub literal: String,
+    pub span: Span,
 }
 
 impl Token {
@@ -40,6 +166,15 @@ impl Token {
         Token {
             tokentype: tktype,
             literal,
+            span: Span::default(),
+        }
+    }
+
+    pub fn with_span(tktype: TokenType, literal: String, span: Span) -> Token {
+        Token {
+            tokentype: tktype,
+            literal,
+            span,
         }
     }
 }
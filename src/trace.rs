@@ -0,0 +1,54 @@
+//! Tracing of the parser: a tracer plugged in the parser is told each time a parse function is
+//! entered or exited, which helps understanding how an expression was parsed.
+use std::io::Write;
+
+use crate::token::Token;
+
+/// Receives the parse functions entered and exited by the parser. `depth` is the number of parse
+/// functions being run when the function is entered, `token` the current token.
+pub trait ParserTracer {
+    fn enter(&mut self, depth: usize, function: &str, token: &Token);
+    fn exit(&mut self, depth: usize, function: &str, token: &Token);
+}
+
+/// Tracer writing an indented trace of the parse functions, one line per entered or exited
+/// function.
+pub struct IndentTracer<W: Write> {
+    output: W,
+}
+
+impl IndentTracer<std::io::Stderr> {
+    /// Tracer writing to the standard error
+    pub fn stderr() -> IndentTracer<std::io::Stderr> {
+        IndentTracer::new(std::io::stderr())
+    }
+}
+
+impl<W: Write> IndentTracer<W> {
+    pub fn new(output: W) -> IndentTracer<W> {
+        IndentTracer { output }
+    }
+
+    fn trace(&mut self, depth: usize, event: &str, function: &str, token: &Token) {
+        // A tracer must not make the parsing fail, errors of the output are ignored
+        let _ = writeln!(
+            self.output,
+            "{:indent$}{} {} {:?}",
+            "",
+            event,
+            function,
+            token.tokentype,
+            indent = 2 * depth
+        );
+    }
+}
+
+impl<W: Write> ParserTracer for IndentTracer<W> {
+    fn enter(&mut self, depth: usize, function: &str, token: &Token) {
+        self.trace(depth, "BEGIN", function, token);
+    }
+
+    fn exit(&mut self, depth: usize, function: &str, token: &Token) {
+        self.trace(depth, "END", function, token);
+    }
+}
@@ -1,6 +1,13 @@
 pub use Monkey::token::*;
 #[cfg(test)]
 use Monkey::Lexer;
+use Monkey::{relex, Edition, LexError, LexWarning, TextEdit};
+use Monkey::TokenBuilder;
+
+/// Display the errors of the lexer as the user would see them
+fn error_messages(lexer: &Lexer) -> Vec<String> {
+    lexer.get_errors().iter().map(|error| error.to_string()).collect()
+}
 
 #[test]
 /// Check that the read_char function of the lexer works properly
@@ -63,3 +70,615 @@ fn read_identifier_works() {
         assert_eq!(mytoken.tokentype, *tok);
     }
 }
+
+/// Check that the keywords of loops are read properly
+#[test]
+fn read_loop_keywords_works() {
+    let input: String = "while (x) { break; continue } whiles".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::WHILE,
+        TokenType::LPAREN,
+        TokenType::IDENT("x".to_string()),
+        TokenType::RPAREN,
+        TokenType::LBRACE,
+        TokenType::BREAK,
+        TokenType::SEMICOLON,
+        TokenType::CONTINUE,
+        TokenType::RBRACE,
+        TokenType::IDENT("whiles".to_string()),
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+}
+
+/// Check that for loops and ranges are read properly, a range of integers not being a float
+#[test]
+fn read_for_and_ranges_works() {
+    let input: String = "for (i in 0..10) { 1.5..=a }".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::FOR,
+        TokenType::LPAREN,
+        TokenType::IDENT("i".to_string()),
+        TokenType::IN,
+        TokenType::INT(0),
+        TokenType::DOTDOT,
+        TokenType::INT(10),
+        TokenType::RPAREN,
+        TokenType::LBRACE,
+        TokenType::FLOAT(1.5),
+        TokenType::DOTDOTEQUAL,
+        TokenType::IDENT("a".to_string()),
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+    assert!(!my_lexer.has_errors());
+}
+
+/// Check that the kind of a token is named without its value
+#[test]
+fn token_kind_name_works() {
+    assert_eq!(TokenType::IDENT("five".to_string()).kind_name(), "IDENT");
+    assert_eq!(TokenType::INT(5).kind_name(), "INT");
+    assert_eq!(TokenType::KEYWORD("unless".to_string()).kind_name(), "KEYWORD");
+    assert_eq!(TokenType::DOTDOTEQUAL.kind_name(), "DOTDOTEQUAL");
+}
+
+/// Check that every token knows where it comes from in the input
+#[test]
+fn token_spans_works() {
+    let input: String = "let x = 10;\n  x == 5".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = vec![
+        (TokenType::LET, Span::new(0, 3, 1, 1)),
+        (TokenType::IDENT("x".to_string()), Span::new(4, 5, 1, 5)),
+        (TokenType::ASSIGN, Span::new(6, 7, 1, 7)),
+        (TokenType::INT(10), Span::new(8, 10, 1, 9)),
+        (TokenType::SEMICOLON, Span::new(10, 11, 1, 11)),
+        (TokenType::IDENT("x".to_string()), Span::new(14, 15, 2, 3)),
+        (TokenType::EQUAL, Span::new(16, 18, 2, 5)),
+        (TokenType::INT(5), Span::new(19, 20, 2, 8)),
+        (TokenType::EOF, Span::new(20, 20, 2, 9)),
+    ];
+
+    for (tok, span) in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+        assert_eq!(mytoken.span, *span);
+    }
+}
+
+/// Check that line and block comments are skipped, even when nested
+#[test]
+fn skip_comments_works() {
+    let input: String = "let // a comment\nx /* a /* nested */ comment */ = 5; // end".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::LET,
+        TokenType::IDENT("x".to_string()),
+        TokenType::ASSIGN,
+        TokenType::INT(5),
+        TokenType::SEMICOLON,
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+    assert!(!my_lexer.has_errors());
+}
+
+/// Check that doc comments are kept as tokens and that an unterminated block comment is reported
+#[test]
+fn doc_comments_works() {
+    let input: String = "/// The answer\n//// not a doc\nlet /* never closed".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::DOC("The answer".to_string()),
+        TokenType::LET,
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+    assert_eq!(
+        error_messages(&my_lexer),
+        vec!["3:5: Unterminated block comment".to_string()]
+    );
+}
+
+/// Check that string literals are decoded, raw strings included
+#[test]
+fn read_string_works() {
+    let input: String = r#""hello world" "a\tb\n\\\"" "caf\u{e9}" r"C:\path\n""#.to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::STRING("hello world".to_string()),
+        TokenType::STRING("a\tb\n\\\"".to_string()),
+        TokenType::STRING("café".to_string()),
+        TokenType::STRING("C:\\path\\n".to_string()),
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+    assert!(!my_lexer.has_errors());
+}
+
+/// Check that bad escapes and unterminated strings are reported with their location
+#[test]
+fn read_string_errors_works() {
+    let input: String = r#"let s = "bad \q escape"; "unterminated"#.to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::LET,
+        TokenType::IDENT("s".to_string()),
+        TokenType::ASSIGN,
+        TokenType::STRING("bad \\q escape".to_string()),
+        TokenType::SEMICOLON,
+        TokenType::ILLEGAL,
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+    assert_eq!(
+        error_messages(&my_lexer),
+        vec![
+            "1:14: Invalid escape sequence \\q".to_string(),
+            "1:26: Unterminated string literal".to_string(),
+        ]
+    );
+}
+
+/// Check that floats are recognised, with or without exponent
+#[test]
+fn read_float_works() {
+    let input: String = "2.75 1e-9 2.5E+3 10e2 7. 42".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::FLOAT(2.75),
+        TokenType::FLOAT(1e-9),
+        TokenType::FLOAT(2.5e3),
+        TokenType::FLOAT(10e2),
+        TokenType::INT(7),
+        TokenType::ILLEGAL,
+        TokenType::INT(42),
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+}
+
+/// Check prefixed and underscore separated integers
+#[test]
+fn read_integer_radix_works() {
+    let input: String = "0xff 0o17 0b1010 1_000_000 0x_dead_BEEF 1_0.2_5".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::INT(255),
+        TokenType::INT(15),
+        TokenType::INT(10),
+        TokenType::INT(1_000_000),
+        TokenType::INT(0xdead_beef),
+        TokenType::FLOAT(10.25),
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+    assert!(!my_lexer.has_errors());
+}
+
+/// Check that oversized and malformed integers are reported instead of panicking
+#[test]
+fn read_integer_errors_works() {
+    let input: String = "99999999999999999999999 0b102 0x;".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::ILLEGAL,
+        TokenType::ILLEGAL,
+        TokenType::ILLEGAL,
+        TokenType::SEMICOLON,
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+    assert_eq!(
+        my_lexer.get_errors(),
+        vec![
+            LexError::NumberOverflow {
+                text: "99999999999999999999999".to_string(),
+                span: Span::new(0, 23, 1, 1),
+            },
+            LexError::InvalidDigit {
+                found: '2',
+                radix: 2,
+                text: "0b102".to_string(),
+                span: Span::new(24, 29, 1, 25),
+            },
+            LexError::MissingDigits {
+                text: "0x".to_string(),
+                span: Span::new(30, 32, 1, 31),
+            },
+        ]
+    );
+    assert_eq!(
+        my_lexer.get_errors()[0].to_string(),
+        format!(
+            "1:1: Integer literal 99999999999999999999999 is too large, maximum is {}",
+            usize::MAX
+        )
+    );
+}
+
+/// Check that non ASCII characters are lexed, in identifiers as well as in strings
+#[test]
+fn read_unicode_works() {
+    let input: String = "let été = \"déjà vu\"; foo1 + _x2 € bar".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        (TokenType::LET, "let"),
+        (TokenType::IDENT("été".to_string()), "été"),
+        (TokenType::ASSIGN, "="),
+        (TokenType::STRING("déjà vu".to_string()), "\"déjà vu\""),
+        (TokenType::SEMICOLON, ";"),
+        (TokenType::IDENT("foo1".to_string()), "foo1"),
+        (TokenType::PLUS, "+"),
+        (TokenType::IDENT("_x2".to_string()), "_x2"),
+        (TokenType::ILLEGAL, "€"),
+        (TokenType::IDENT("bar".to_string()), "bar"),
+        (TokenType::EOF, ""),
+    ];
+
+    for (tok, literal) in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+        assert_eq!(mytoken.literal, *literal);
+    }
+}
+
+/// Check that tabs, line feeds and Windows line endings are skipped and counted
+#[test]
+fn skip_whitespace_works() {
+    let input: String = "let\tx = 1;\r\n\tx\x0c+\n\r\n y".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        (TokenType::LET, 1, 1),
+        (TokenType::IDENT("x".to_string()), 1, 5),
+        (TokenType::ASSIGN, 1, 7),
+        (TokenType::INT(1), 1, 9),
+        (TokenType::SEMICOLON, 1, 10),
+        (TokenType::IDENT("x".to_string()), 2, 2),
+        (TokenType::PLUS, 2, 4),
+        (TokenType::IDENT("y".to_string()), 4, 2),
+        (TokenType::EOF, 4, 3),
+    ];
+
+    for (tok, line, column) in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+        assert_eq!((mytoken.span.line, mytoken.span.column), (*line, *column));
+    }
+    assert!(!my_lexer.has_errors());
+}
+
+/// Check that stray control characters are only reported in strict mode
+#[test]
+fn strict_mode_works() {
+    let input: String = "x\x07 = 1".to_string();
+    let mut my_lexer = Lexer::new(input.clone());
+    while my_lexer.next_token().tokentype != TokenType::EOF {}
+    assert!(!my_lexer.has_errors());
+
+    let mut my_lexer = Lexer::new(input);
+    my_lexer.set_strict_mode(true);
+    while my_lexer.next_token().tokentype != TokenType::EOF {}
+    assert_eq!(
+        error_messages(&my_lexer),
+        vec!["1:2: Unexpected control character U+0007".to_string()]
+    );
+}
+
+/// Check that the lexer can be used as an iterator which stops after the EOF token
+#[test]
+fn lexer_iterator_works() {
+    let mut my_lexer = Lexer::new("let x = 5;".to_string());
+    let tokens: Vec<TokenType> = my_lexer.by_ref().map(|tok| tok.tokentype).collect();
+    assert_eq!(
+        tokens,
+        vec![
+            TokenType::LET,
+            TokenType::IDENT("x".to_string()),
+            TokenType::ASSIGN,
+            TokenType::INT(5),
+            TokenType::SEMICOLON,
+            TokenType::EOF,
+        ]
+    );
+    assert_eq!(my_lexer.next(), None);
+    assert_eq!(my_lexer.next(), None);
+}
+
+/// Check that the lexer can be moved to another thread, whatever its input
+#[test]
+fn lexer_is_send() {
+    fn assert_send<T: Send>(_: &T) {}
+    assert_send(&Lexer::new("let x = 1;"));
+    assert_send(&Lexer::from_reader(std::io::Cursor::new("let x = 1;")));
+}
+
+/// Check that lexing from a reader gives the same tokens as lexing the whole input
+#[test]
+fn lexer_from_reader_works() {
+    let input = "/// Doc\nlet été = \"a\nb\";\r\n/* multi\nline */ x >= 0x1F;\n".to_string();
+    let reader = std::io::BufReader::with_capacity(4, std::io::Cursor::new(input.clone()));
+    let expected: Vec<Token> = Lexer::new(input).collect();
+    let tokens: Vec<Token> = Lexer::from_reader(reader).collect();
+    assert_eq!(tokens, expected);
+}
+
+/// Check that borrowed tokens point into the input and that identifiers are interned
+#[test]
+fn borrowed_lexer_works() {
+    let input = "/// Doc\nlet x = \"plain\"; x + \"esc\\n\" + y;";
+    let mut my_lexer = Monkey::BorrowedLexer::new(input);
+    let tokens: Vec<Monkey::BorrowedToken> = my_lexer.by_ref().collect();
+
+    let x = my_lexer.symbols().get("x").unwrap();
+    let y = my_lexer.symbols().get("y").unwrap();
+    assert_eq!(my_lexer.symbols().len(), 2);
+    assert_eq!(tokens[0].tokentype, Monkey::BorrowedTokenType::DOC("Doc"));
+    assert_eq!(tokens[2].tokentype, Monkey::BorrowedTokenType::IDENT(x));
+    assert_eq!(tokens[6].tokentype, Monkey::BorrowedTokenType::IDENT(x));
+    assert_eq!(tokens[10].tokentype, Monkey::BorrowedTokenType::IDENT(y));
+    assert!(matches!(
+        &tokens[4].tokentype,
+        Monkey::BorrowedTokenType::STRING(std::borrow::Cow::Borrowed("plain"))
+    ));
+    assert!(matches!(
+        &tokens[8].tokentype,
+        Monkey::BorrowedTokenType::STRING(std::borrow::Cow::Owned(value)) if value == "esc\n"
+    ));
+    for token in tokens.iter() {
+        let offset = token.literal.as_ptr() as usize - input.as_ptr() as usize;
+        assert_eq!(offset, token.span.start);
+    }
+
+    // Converting the tokens back gives what the owned lexer produces
+    let owned: Vec<Token> = tokens
+        .iter()
+        .map(|token| token.to_token(my_lexer.symbols()))
+        .collect();
+    let expected: Vec<Token> = Lexer::new(input).collect();
+    assert_eq!(owned, expected);
+}
+
+/// Check the operators made of one or two chars
+#[test]
+fn read_operators_works() {
+    let input: String = "<= >= < > % ** * && & || | ^ << >> ... [ ] : += -= *= /=".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let expected_results = [
+        TokenType::LTEQUAL,
+        TokenType::GTEQUAL,
+        TokenType::LT,
+        TokenType::GT,
+        TokenType::PERCENT,
+        TokenType::POWER,
+        TokenType::ASTERISK,
+        TokenType::AND,
+        TokenType::AMPERSAND,
+        TokenType::OR,
+        TokenType::PIPE,
+        TokenType::CARET,
+        TokenType::LSHIFT,
+        TokenType::RSHIFT,
+        TokenType::ELLIPSIS,
+        TokenType::LBRACKET,
+        TokenType::RBRACKET,
+        TokenType::COLON,
+        TokenType::PLUSASSIGN,
+        TokenType::MINUSASSIGN,
+        TokenType::ASTERISKASSIGN,
+        TokenType::SLASHASSIGN,
+        TokenType::EOF,
+    ];
+
+    for tok in expected_results.iter() {
+        let mytoken = my_lexer.next_token();
+        assert_eq!(mytoken.tokentype, *tok);
+    }
+}
+
+/// Check that unexpected chars are reported with their location and that lexing goes on
+#[test]
+fn unexpected_char_works() {
+    let input: String = "let a = 1 @ 2;\nlet b € 3;".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let tokens: Vec<TokenType> = my_lexer.by_ref().map(|tok| tok.tokentype).collect();
+    assert_eq!(tokens.len(), 13);
+    assert_eq!(tokens[4], TokenType::ILLEGAL);
+    assert_eq!(tokens[9], TokenType::ILLEGAL);
+    assert_eq!(
+        my_lexer.get_errors(),
+        vec![
+            LexError::UnexpectedChar {
+                found: '@',
+                span: Span::new(10, 11, 1, 11),
+            },
+            LexError::UnexpectedChar {
+                found: '€',
+                span: Span::new(21, 24, 2, 7),
+            },
+        ]
+    );
+    assert_eq!(error_messages(&my_lexer)[1], "2:7: Unexpected character €");
+}
+
+/// Check that the lexer consults the keyword registry it was built with
+#[test]
+fn keyword_registry_works() {
+    let mut keywords = TokenBuilder::default();
+    keywords.add_custom_keyword("unless");
+    keywords.add_keyword("func", TokenType::FUNCTION);
+    assert_eq!(keywords.remove_keyword("return"), Some(TokenType::RETURN));
+
+    let input: String = "unless func return fn".to_string();
+    let tokens: Vec<TokenType> = Lexer::with_keywords(input, keywords)
+        .map(|tok| tok.tokentype)
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            TokenType::KEYWORD("unless".to_string()),
+            TokenType::FUNCTION,
+            TokenType::IDENT("return".to_string()),
+            TokenType::FUNCTION,
+            TokenType::EOF,
+        ]
+    );
+
+    // Legacy spellings follow the keyword they stand for
+    let mut keywords = TokenBuilder::default();
+    assert_eq!(keywords.remove_keyword("fn"), Some(TokenType::FUNCTION));
+    keywords.add_keyword("let", TokenType::KEYWORD("var".to_string()));
+    assert_eq!(keywords.remove_keyword("IF"), Some(TokenType::IF));
+    assert!(!keywords.is_keyword("FN"));
+    let input: String = "fn FN let LET if IF".to_string();
+    let tokens: Vec<TokenType> = Lexer::with_keywords(input, keywords)
+        .map(|tok| tok.tokentype)
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            TokenType::IDENT("fn".to_string()),
+            TokenType::IDENT("FN".to_string()),
+            TokenType::KEYWORD("var".to_string()),
+            TokenType::KEYWORD("var".to_string()),
+            TokenType::IF,
+            TokenType::IDENT("IF".to_string()),
+            TokenType::EOF,
+        ]
+    );
+}
+
+/// Check that the edition pragma selects the keywords and that legacy keywords are deprecated in
+/// the strict edition
+#[test]
+fn edition_pragma_works() {
+    let input: String = "LET x = TRUE;".to_string();
+    let mut my_lexer = Lexer::new(input);
+    assert_eq!(my_lexer.next_token().tokentype, TokenType::LET);
+    my_lexer.by_ref().for_each(drop);
+    assert!(!my_lexer.has_warnings());
+
+    let input: String = "#!edition 2\nLET x = let;".to_string();
+    let mut my_lexer = Lexer::new(input);
+    let tokens: Vec<TokenType> = my_lexer.by_ref().map(|tok| tok.tokentype).collect();
+    assert_eq!(tokens[0], TokenType::LET);
+    assert_eq!(tokens[3], TokenType::LET);
+    assert!(!my_lexer.has_errors());
+    assert_eq!(
+        my_lexer.get_warnings(),
+        vec![LexWarning::DeprecatedKeyword {
+            found: "LET".to_string(),
+            keyword: "let".to_string(),
+            span: Span::new(12, 15, 2, 1),
+        }]
+    );
+    assert_eq!(
+        my_lexer.get_warnings()[0].to_string(),
+        "2:1: Keyword LET is deprecated, use let instead"
+    );
+
+    let mut my_lexer = Lexer::new("#!edition 3\nlet".to_string());
+    assert_eq!(my_lexer.next_token().tokentype, TokenType::LET);
+    assert_eq!(error_messages(&my_lexer), vec!["1:1: Invalid pragma #!edition 3"]);
+
+    let mut my_lexer = Lexer::new("x\n#!edition 2".to_string());
+    my_lexer.by_ref().for_each(drop);
+    assert_eq!(error_messages(&my_lexer), vec!["2:1: Unexpected character #"]);
+
+    let mut my_lexer = Lexer::new("FN(x) { x }".to_string());
+    my_lexer.set_edition(Edition::STRICT);
+    assert_eq!(my_lexer.next_token().tokentype, TokenType::FUNCTION);
+    assert!(my_lexer.has_warnings());
+}
+
+/// Check that lexing again after an edit gives the same tokens as lexing the whole edited code
+#[test]
+fn relex_works() {
+    let input: &str = "let five = 5;\nlet ten = 10;\n/* sum */ let sum = five + ten;\n";
+    let tokens: Vec<Token> = Lexer::new(input).collect();
+    let edits = [
+        TextEdit::new(4..8, "twelve"),
+        TextEdit::new(11..12, "<="),
+        TextEdit::new(12..12, "0"),
+        TextEdit::new(0..0, "#!edition 2\n"),
+        TextEdit::new(14..27, ""),
+        TextEdit::new(30..30, "\n\n"),
+        TextEdit::new(37..37, "*/ x /*"),
+        TextEdit::new(28..28, "\""),
+        TextEdit::new(input.len()..input.len(), "five"),
+    ];
+
+    for edit in edits.iter() {
+        let mut code = input.to_string();
+        edit.apply(&mut code);
+        let relexed = relex(&code, &tokens, edit);
+        let expected: Vec<Token> = Lexer::new(code.as_str()).collect();
+        assert_eq!(relexed.tokens, expected, "Edit {:?}", edit);
+    }
+
+    let mut code = input.to_string();
+    let edit = TextEdit::new(4..8, "twelve");
+    edit.apply(&mut code);
+    let relexed = relex(&code, &tokens, &edit);
+    assert_eq!(relexed.changed, 0..2);
+    assert_eq!(
+        relexed.tokens[1].tokentype,
+        TokenType::IDENT("twelve".to_string())
+    );
+
+    // Only the tokens around a large deletion are lexed again
+    let input: String = "let x = 1;\n".repeat(200);
+    let tokens: Vec<Token> = Lexer::new(input.as_str()).collect();
+    let edits = [
+        (TextEdit::new(100..110, ""), 44..46),
+        (TextEdit::new(100..400, ""), 44..46),
+    ];
+    for (edit, changed) in edits.iter() {
+        let mut code = input.clone();
+        edit.apply(&mut code);
+        let relexed = relex(&code, &tokens, edit);
+        let expected: Vec<Token> = Lexer::new(code.as_str()).collect();
+        assert_eq!(relexed.tokens, expected, "Edit {:?}", edit);
+        assert_eq!(relexed.changed, *changed, "Edit {:?}", edit);
+    }
+}
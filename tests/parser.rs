@@ -1,8 +1,9 @@
 use Monkey::*;
 pub use Monkey::token::*;
 #[cfg(test)]
-
 use Monkey::Parser;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
 
 #[test]
 fn test_let_statement() {
@@ -59,16 +60,16 @@ fn test_return_statement() {
 
 fn assert_identifier(test_stmt: Stmt, name: &str) -> bool {
     let cmp_stmt = Stmt::EXPRESSION(Expr::IDENTIFIER(Ident(name.to_string())));
-    matches!(cmp_stmt, test_stmt)
+    cmp_stmt == test_stmt
 }
 
 
 fn assert_identifier_expr(test_expr: Expr, name: &str) -> bool{
     let cmp_stmt = Expr::IDENTIFIER(Ident(name.to_string()));
-    matches!(cmp_stmt, test_stmt)
+    cmp_stmt == test_expr
 }
 
-//#[test]
+#[test]
 fn test_boolean_expression(){
     let input = "true; false; let foobar = true; let barfoo = false;".to_string();
     let mut parser = Parser::new(input);
@@ -126,14 +127,15 @@ fn test_prefix_exp(){
 #[test]
 fn test_operator_precedence(){
     type ProgramInput = (String, String); 
-    let mut programs_inputs : Vec<ProgramInput> = Vec::new();
-    programs_inputs.push(("-a * b".to_string(), "((-a) * b)".to_string()));
-    programs_inputs.push(("!-a".to_string(), "(!(-a))".to_string()));
-    programs_inputs.push(("a+b+c".to_string(), "((a + b) + c)".to_string()));
-    programs_inputs.push(("a+b-c".to_string(), "((a + b) - c)".to_string()));
-    programs_inputs.push(("a*b*c".to_string(), "((a * b) * c)".to_string()));
-    programs_inputs.push(("a*b/c".to_string(), "((a * b) / c)".to_string()));
-    programs_inputs.push(("1+(2+3)+4".to_string(), "((1 + (2 + 3)) + 4)".to_string()));
+    let programs_inputs : Vec<ProgramInput> = vec![
+        ("-a * b".to_string(), "((-a) * b)".to_string()),
+        ("!-a".to_string(), "(!(-a))".to_string()),
+        ("a+b+c".to_string(), "((a + b) + c)".to_string()),
+        ("a+b-c".to_string(), "((a + b) - c)".to_string()),
+        ("a*b*c".to_string(), "((a * b) * c)".to_string()),
+        ("a*b/c".to_string(), "((a * b) / c)".to_string()),
+        ("1+(2+3)+4".to_string(), "((1 + (2 + 3)) + 4)".to_string()),
+    ];
 
 
 
@@ -154,6 +156,38 @@ fn test_ifelse(){
     assert_eq!(input, program[0].to_string());
 }
 
+#[test]
+fn test_else_branches(){
+    let programs_inputs = [
+        ("if (x < y) { x } else { y }", "if (x < y) { x } else { y }"),
+        ("if x < y { x } else { y }", "if (x < y) { x } else { y }"),
+        ("if a { 1 } else if b { 2 } else { 3 }", "if a { 1 } else if b { 2 } else { 3 }"),
+        ("if (a) { } else { }", "if a {  } else {  }"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(program.len(), 1);
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    let mut parser = Parser::new("if x { 1 } else if y { 2 }".to_string());
+    let program = parser.parseprogramm();
+    let one = BlockStatement(vec![Stmt::EXPRESSION(Expr::INTEGER(1))]);
+    let two = BlockStatement(vec![Stmt::EXPRESSION(Expr::INTEGER(2))]);
+    let else_if = Expr::IF(Box::new(Expr::IDENTIFIER(Ident("y".to_string()))), two, None);
+    assert_eq!(program[0], Stmt::EXPRESSION(Expr::IF(
+        Box::new(Expr::IDENTIFIER(Ident("x".to_string()))),
+        one,
+        Some(BlockStatement(vec![Stmt::EXPRESSION(else_if)])),
+    )));
+
+    let mut parser = Parser::new("if x { 1 } else 2".to_string());
+    parser.parseprogramm();
+    assert!(parser.has_errors());
+}
+
 #[test]
 fn test_functions(){
     type ProgramInput = (String, usize, Vec<String>);
@@ -168,14 +202,12 @@ fn test_functions(){
         let program = parser.parseprogramm();
         assert_eq!(program.len(), 1);
 
-        if let Stmt::EXPRESSION(Expr::FUNC(Parameters(param_list), _stmt)) = program[0].clone() {
+        if let Stmt::EXPRESSION(Expr::FUNC(param_list, _stmt)) = program[0].clone() {
             assert_eq!(param_list.len(), *args_nb);
 
         //Now let's compare identifiers and strings in the vector
         for i in 0..args_vec.len() {
-            if let Expr::IDENTIFIER(Ident(identifier_name)) = param_list[i].clone() {
-                assert_eq!(identifier_name, args_vec[i]);
-            }
+            assert_eq!(param_list[i], Param::new(Ident(args_vec[i].clone())));
         }
         }else {
             println!("Expected an Express, go {:?} instead", program[0].clone());
@@ -190,6 +222,35 @@ fn test_functions(){
 }
 
 
+#[test]
+fn test_function_parameters(){
+    let mut parser = Parser::new("fn(a, b = 2 * 3, ...rest) { a }".to_string());
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+    if let Stmt::EXPRESSION(Expr::FUNC(params, _block)) = &program[0] {
+        let mut b = Param::new(Ident("b".to_string()));
+        b.default = Some(Expr::INFIX(Box::new(Expr::INTEGER(2)), Infix::MULTIPLY, Box::new(Expr::INTEGER(3))));
+        let mut rest = Param::new(Ident("rest".to_string()));
+        rest.rest = true;
+        assert_eq!(*params, vec![Param::new(Ident("a".to_string())), b, rest]);
+    } else {
+        panic!("Expected a function, got {:?} instead", program[0]);
+    }
+    assert_eq!(program[0].to_string(), "fn (a,b = (2 * 3),...rest){ a }");
+
+    let mut parser = Parser::new("fn(1 + 2) { }".to_string());
+    assert!(parser.parseprogramm().is_empty());
+    assert_eq!(parser.get_errors(), vec![ParseError::InvalidParameter{found: TokenType::INT(1), span: Span::new(3, 4, 1, 4)}]);
+
+    let mut parser = Parser::new("fn(a, b, a) { }".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors(), vec![ParseError::DuplicateParameter{name: "a".to_string(), span: Span::new(9, 10, 1, 10)}]);
+
+    let mut parser = Parser::new("fn(...rest, a) { }".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors()[0].to_string(), "1:4: Rest parameter rest must be the last parameter");
+}
+
 #[test]
 fn test_function_call(){
     type ProgramInput = (String, usize, Vec<String>);
@@ -216,3 +277,590 @@ fn test_function_call(){
     }
 }
 
+
+#[test]
+fn test_doc_comments(){
+    let input = "/// First line\n/// Second line\nlet x = 5;".to_string();
+    let mut parser = Parser::new(input);
+    let program = parser.parseprogramm();
+    assert_eq!(program.len(), 1);
+    assert!(!parser.has_errors());
+
+    if let Stmt::DOCUMENTED(doc, stmt) = program[0].clone() {
+        assert_eq!(doc, "First line\nSecond line");
+        assert!(assert_let_statement(*stmt, "x", "5"));
+    }else {
+        panic!("Expected a documented let statement, got {:?} instead", program[0]);
+    }
+
+    // Doc comments before anything else than a let statement are ignored
+    let inputs = [
+        ("/// note\nx + 1; /// trailing", 1, 2),
+        ("fn() { let x = 1;\n/// note\n}", 1, 1),
+    ];
+    for (given, statements, warnings) in inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(program.len(), *statements);
+        assert_eq!(parser.get_warnings().len(), *warnings);
+    }
+    let mut parser = Parser::new("/// note\nx + 1; /// trailing".to_string());
+    parser.parseprogramm();
+    let warnings = parser.get_warnings();
+    assert_eq!(warnings[0], ParseWarning::DanglingDocComment{
+        found: TokenType::IDENT("x".to_string()),
+        span: Span::new(9, 10, 2, 1),
+    });
+    assert_eq!(warnings[1].to_string(), "2:20: Doc comment is not followed by a let statement - Found EOF instead");
+}
+
+#[test]
+fn test_string_literal_exp(){
+    let input = r#"let greeting = "hello" ;"#.to_string();
+    let mut parser = Parser::new(input);
+    let program = parser.parseprogramm();
+    assert_eq!(program.len(), 1);
+    assert_eq!(program[0], Stmt::LET(Ident("greeting".to_string()), Expr::STRING("hello".to_string())));
+}
+
+#[test]
+fn test_float_literal_exp(){
+    let input = "let pi = 2.75; 1.5 * 2e-1".to_string();
+    let mut parser = Parser::new(input);
+    let program = parser.parseprogramm();
+    assert_eq!(program.len(), 2);
+    assert_eq!(program[0], Stmt::LET(Ident("pi".to_string()), Expr::FLOAT(2.75)));
+    assert_eq!(program[1].to_string(), "(1.5 * 0.2)");
+}
+
+#[test]
+fn test_extended_operator_precedence(){
+    let programs_inputs = [
+        ("a <= b == c >= d", "((a <= b) == (c >= d))"),
+        ("a % b + c", "((a % b) + c)"),
+        ("a ** b ** c", "(a ** (b ** c))"),
+        ("a * b ** c", "(a * (b ** c))"),
+        ("-a ** b", "(-(a ** b))"),
+        ("a || b && c", "(a || (b && c))"),
+        ("a && b == c", "(a && (b == c))"),
+        ("a | b ^ c & d", "(a | (b ^ (c & d)))"),
+        ("a & b == c", "((a & b) == c)"),
+        ("a << b + c >> d", "((a << (b + c)) >> d)"),
+        ("a + b < c << d", "((a + b) < (c << d))"),
+    ];
+
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert_eq!(*expected, program[0].to_string());
+    }
+}
+
+// Desugar `emit x;` into a call to the emit function
+fn parse_emit_statement(parser: &mut Parser) -> Option<Stmt> {
+    let func = Expr::IDENTIFIER(Ident(parser.get_cur_token().literal.clone()));
+    parser.next_token();
+    let arg = parser.parse_expression(Precedence::LOWEST)?;
+    if !parser.expect_next_token(&TokenType::SEMICOLON) {
+        return None;
+    }
+    Some(Stmt::EXPRESSION(Expr::CALL(Box::new(func), Parameters(vec![arg]))))
+}
+
+#[test]
+fn test_custom_statement(){
+    let mut keywords = TokenBuilder::default();
+    keywords.add_custom_keyword("emit");
+    let mut parser = Parser::with_keywords("emit x + 1; let y = 2;".to_string(), keywords.clone());
+    parser.add_statement_parser("emit", parse_emit_statement);
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors());
+    assert_eq!(program.len(), 2);
+    assert_eq!(program[0].to_string(), "emit((x + 1))");
+
+    let mut parser = Parser::with_keywords("emit x;".to_string(), keywords);
+    let program = parser.parseprogramm();
+    assert!(program.is_empty());
+    assert_eq!(parser.get_errors(), vec![ParseError::UnknownStatement{keyword: "emit".to_string(), span: Span::new(0, 4, 1, 1)}]);
+    assert_eq!(parser.get_errors()[0].to_string(), "1:1: No statement parser registered for keyword \"emit\"");
+}
+
+#[test]
+fn test_strict_edition(){
+    let keywords = TokenBuilder::for_edition(Edition::STRICT);
+    let mut parser = Parser::with_keywords("LET x = 5; let y = 6;".to_string(), keywords);
+    let program = parser.parseprogramm();
+    assert_eq!(program.len(), 2);
+    assert!(!parser.has_errors());
+    let warnings = parser.get_warnings();
+    assert_eq!(warnings, vec![ParseWarning::Lex(LexWarning::DeprecatedKeyword{
+        found: "LET".to_string(),
+        keyword: "let".to_string(),
+        span: Span::new(0, 3, 1, 1),
+    })]);
+    assert_eq!(warnings[0].span(), Span::new(0, 3, 1, 1));
+    assert_eq!(warnings[0].to_string(), "1:1: Keyword LET is deprecated, use let instead");
+}
+
+#[test]
+fn test_parse_errors(){
+    let mut parser = Parser::new("let = 5;".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors()[0], ParseError::UnexpectedToken{
+        expected: TokenType::IDENT(String::new()),
+        found: TokenType::ASSIGN,
+        span: Span::new(4, 5, 1, 5),
+    });
+    assert_eq!(parser.get_errors()[0].to_string(), "1:5: Expected token IDENT was not found - Found ASSIGN instead");
+
+    let mut parser = Parser::new("let x = (1 + 2".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors(), vec![ParseError::UnclosedDelimiter{delimiter: TokenType::LPAREN, span: Span::new(8, 9, 1, 9)}]);
+
+    let mut parser = Parser::new("let x = ;".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors()[0], ParseError::MissingExpression{found: TokenType::SEMICOLON, span: Span::new(8, 9, 1, 9)});
+
+    let mut parser = Parser::new("let x = 1 @ 2;".to_string());
+    parser.parseprogramm();
+    let errors = parser.get_errors();
+    assert!(matches!(errors[0], ParseError::Lex(LexError::UnexpectedChar{found: '@', ..})));
+    assert_eq!(errors[0].span(), Span::new(10, 11, 1, 11));
+
+    // Illegal tokens are only reported by the lexer
+    let inputs = [
+        "let x = 99999999999999999999999;",
+        "let x = 1 @ 2;",
+        "let x = \"unterminated",
+        "let x = 0x;",
+        "let @ = 1;",
+        "f(1, #);",
+    ];
+    for given in inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        parser.parseprogramm();
+        let errors = parser.get_errors();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(matches!(errors[0], ParseError::Lex(_)));
+    }
+
+    let mut parser = Parser::new("add(1, 2); fn(x) { x }".to_string());
+    parser.parseprogramm();
+    assert!(!parser.has_errors());
+}
+
+// Output shared between the test and the tracer owned by the parser
+#[derive(Clone, Default)]
+struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parser_tracer(){
+    let output = SharedOutput::default();
+    let mut parser = Parser::new("-a;".to_string());
+    parser.set_tracer(Box::new(IndentTracer::new(output.clone())));
+    parser.parseprogramm();
+
+    let trace = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let expected = [
+        "BEGIN parse_statement MINUS",
+        "  BEGIN parse_expression MINUS",
+        "    BEGIN parse_prefix_expression MINUS",
+        "      BEGIN parse_expression IDENT(\"a\")",
+        "      END parse_expression IDENT(\"a\")",
+        "    END parse_prefix_expression IDENT(\"a\")",
+        "  END parse_expression IDENT(\"a\")",
+        "END parse_statement SEMICOLON",
+    ];
+    assert_eq!(trace.lines().collect::<Vec<&str>>(), expected);
+}
+
+#[test]
+fn test_parser_is_send(){
+    fn assert_send<T: Send>(_: &T) {}
+    let mut parser = Parser::new("let x = 1;".to_string());
+    assert_send(&parser);
+    let output = SharedOutput::default();
+    parser.set_tracer(Box::new(IndentTracer::new(output.clone())));
+    assert_send(&parser);
+    let program = std::thread::spawn(move || parser.parseprogramm()).join().unwrap();
+    assert_eq!(program.len(), 1);
+    assert!(!output.0.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_error_recovery(){
+    let input = "let = 5; let x = 1 +; return; let y = add(1, , 2); let z = 3; fn(x { x }; if (x) { let = 2; y } let w = 4;".to_string();
+    let mut parser = Parser::new(input);
+    let program = parser.parseprogramm();
+    let names: Vec<String> = program.iter().filter_map(|stmt| match stmt {
+        Stmt::LET(Ident(name), _) => Some(name.clone()),
+        _ => None,
+    }).collect();
+    assert_eq!(names, vec!["z".to_string(), "w".to_string()]);
+    assert_eq!(parser.get_errors().len(), 6);
+}
+
+#[test]
+fn test_malformed_input_does_not_panic(){
+    let inputs = [
+        "", ";", "}", "{", "(", ")", "let", "let x", "let x =", "return", "-", "!",
+        "1 +", "a * (b", "fn", "fn(", "fn(x,", "fn(x, y) {", "if", "if (", "if (x) {",
+        "add(", "add(1,", "add(1 2)", "/// doc", "}}}; let x = 1;", "\"unterminated",
+        "0x", "1 @ 2", "let x = fn(a, b) { return a +; };",
+    ];
+    for input in inputs.iter() {
+        let mut parser = Parser::new(input.to_string());
+        parser.parseprogramm();
+    }
+
+    let deep_inputs = [
+        format!("{}1{}", "(".repeat(1000), ")".repeat(1000)),
+        "{".repeat(1000),
+        "{".repeat(100000),
+        format!("{}{}", "{ x; ".repeat(1000), "}".repeat(1000)),
+    ];
+    for input in deep_inputs.iter() {
+        let mut parser = Parser::new(input.to_string());
+        parser.parseprogramm();
+        let errors = parser.get_errors();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(matches!(errors[0], ParseError::NestingTooDeep{..}));
+    }
+
+    // The limit is reported once per statement, parsing goes on after the nested code
+    let input = format!("{}{} let = 1; {}", "{ x; ".repeat(1000), "}".repeat(1000), "[".repeat(1000));
+    let mut parser = Parser::new(input);
+    parser.parseprogramm();
+    let errors = parser.get_errors();
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(matches!(errors[0], ParseError::NestingTooDeep{..}));
+    assert!(matches!(errors[1], ParseError::UnexpectedToken{..}));
+    assert!(matches!(errors[2], ParseError::NestingTooDeep{..}));
+}
+
+// Desugar `a <keyword> b` into a call to the function named after the keyword
+fn parse_keyword_operator(parser: &mut Parser, left: Expr) -> Option<Expr> {
+    let func = Expr::IDENTIFIER(Ident(parser.get_cur_token().literal.clone()));
+    let precedence = parser.cur_precedence();
+    parser.next_token();
+    let right = parser.parse_expression(precedence)?;
+    Some(Expr::CALL(Box::new(func), Parameters(vec![left, right])))
+}
+
+// Parse `#x` as the length of x
+fn parse_len(parser: &mut Parser) -> Option<Expr> {
+    parser.next_token();
+    let arg = parser.parse_expression(Precedence::PREFIX)?;
+    Some(Expr::CALL(Box::new(Expr::IDENTIFIER(Ident("len".to_string()))), Parameters(vec![arg])))
+}
+
+#[test]
+fn test_custom_operators(){
+    let mut keywords = TokenBuilder::default();
+    keywords.add_custom_keyword("mod");
+    keywords.add_custom_keyword("cons");
+    keywords.add_custom_keyword("len");
+    let input = "a + b mod c * d; 1 cons 2 cons 3 + 4; len x + y".to_string();
+    let mut parser = Parser::with_keywords(input, keywords);
+    parser.add_infix_operator(&TokenType::KEYWORD("mod".to_string()), Precedence::PRODUCT, Associativity::LEFT, parse_keyword_operator);
+    parser.add_infix_operator(&TokenType::KEYWORD("cons".to_string()), Precedence::SUM, Associativity::RIGHT, parse_keyword_operator);
+    parser.add_prefix_parser(&TokenType::KEYWORD("len".to_string()), parse_len);
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+    assert_eq!(program[0].to_string(), "(a + (mod(b,c) * d))");
+    assert_eq!(program[1].to_string(), "(cons(1,cons(2,3)) + 4)");
+    assert_eq!(program[2].to_string(), "(len(x) + y)");
+
+    // The built-in parse functions handle keyword operators too
+    let mut keywords = TokenBuilder::default();
+    keywords.add_custom_keyword("mod");
+    keywords.add_custom_keyword("not");
+    let mut parser = Parser::with_keywords("not x; a + b mod c".to_string(), keywords);
+    parser.add_prefix_parser(&TokenType::KEYWORD("not".to_string()), Parser::parse_prefix_expression);
+    parser.add_infix_operator(&TokenType::KEYWORD("mod".to_string()), Precedence::PRODUCT, Associativity::LEFT, Parser::parse_infix_expression);
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+    assert_eq!(program[0], Stmt::EXPRESSION(Expr::PREFIX("not".to_string(), Box::new(Expr::IDENTIFIER(Ident("x".to_string()))))));
+    assert_eq!(program[0].to_string(), "(not x)");
+    assert_eq!(program[1].to_string(), "(a + (b mod c))");
+
+    // Tokens the built-in parse functions have no operator for are reported
+    let mut parser = Parser::new("*x; a : b".to_string());
+    parser.add_prefix_parser(&TokenType::ASTERISK, Parser::parse_prefix_expression);
+    parser.add_infix_operator(&TokenType::COLON, Precedence::SUM, Associativity::LEFT, Parser::parse_infix_expression);
+    let program = parser.parseprogramm();
+    assert!(program.is_empty());
+    assert_eq!(parser.get_errors(), vec![
+        ParseError::UnknownOperator{found: TokenType::ASTERISK, span: Span::new(0, 1, 1, 1)},
+        ParseError::UnknownOperator{found: TokenType::COLON, span: Span::new(6, 7, 1, 7)},
+    ]);
+    assert_eq!(parser.get_errors()[1].to_string(), "1:7: Token COLON is not an operator");
+}
+
+#[test]
+fn test_array_and_index_expressions(){
+    let mut parser = Parser::new("[1, 2 * 2, \"three\"]; []".to_string());
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+    assert_eq!(program[0], Stmt::EXPRESSION(Expr::ARRAY(vec![
+        Expr::INTEGER(1),
+        Expr::INFIX(Box::new(Expr::INTEGER(2)), Infix::MULTIPLY, Box::new(Expr::INTEGER(2))),
+        Expr::STRING("three".to_string()),
+    ])));
+    assert_eq!(program[1], Stmt::EXPRESSION(Expr::ARRAY(vec![])));
+
+    let programs_inputs = [
+        ("myArray[1 + 1]", "(myArray[(1 + 1)])"),
+        ("a * [1, 2, 3, 4][b * c] * d", "((a * ([1, 2, 3, 4][(b * c)])) * d)"),
+        ("add(a * b[2], b[1], 2 * [1, 2][1])", "add((a * (b[2])),(b[1]),(2 * ([1, 2][1])))"),
+        ("f(x)[0]", "(f(x)[0])"),
+        ("-a[0]", "(-(a[0]))"),
+        ("a[0][1]", "((a[0])[1])"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    let mut parser = Parser::new("let a = [1, 2".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors(), vec![ParseError::UnclosedDelimiter{delimiter: TokenType::LBRACKET, span: Span::new(8, 9, 1, 9)}]);
+}
+
+#[test]
+fn test_hash_literals(){
+    let mut parser = Parser::new("let h = {\"name\": \"x\", 1: true, a + b: [1]}; f({}, {1: 2})".to_string());
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+    assert_eq!(program.len(), 2);
+    if let Stmt::LET(_, Expr::HASH(pairs)) = &program[0] {
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[0], (Expr::STRING("name".to_string()), Expr::STRING("x".to_string())));
+        assert_eq!(pairs[1], (Expr::INTEGER(1), Expr::BOOLEAN(true)));
+    } else {
+        panic!("Expected a hash literal, got {:?} instead", program[0]);
+    }
+    assert_eq!(program[0].to_string(), "LET h = {\"name\": \"x\", 1: true, (a + b): [1]};");
+    assert_eq!(program[1].to_string(), "f({},{1: 2})");
+}
+
+#[test]
+fn test_hash_or_block_statement(){
+    let mut parser = Parser::new("{\"a\": 1, \"b\": 2}; { let x = 1; x } {} fn() { {1: x} }".to_string());
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+    assert_eq!(program.len(), 4);
+    assert!(matches!(&program[0], Stmt::EXPRESSION(Expr::HASH(pairs)) if pairs.len() == 2));
+    assert!(matches!(&program[1], Stmt::BLOCK(BlockStatement(stmts)) if stmts.len() == 2));
+    assert_eq!(program[2], Stmt::EXPRESSION(Expr::HASH(vec![])));
+    assert_eq!(program[3].to_string(), "fn (){ {1: x} }");
+
+    let mut parser = Parser::new("{ a: 1, b }".to_string());
+    parser.parseprogramm();
+    assert!(matches!(parser.get_errors()[0], ParseError::UnexpectedToken{expected: TokenType::COLON, ..}));
+
+    let mut parser = Parser::new("{ let = 1; y } z".to_string());
+    let program = parser.parseprogramm();
+    assert_eq!(parser.get_errors().len(), 1);
+    assert_eq!(program.len(), 2);
+
+    // Operators may follow a hash starting a statement, and either may end with a semicolon
+    let programs_inputs = [
+        ("{\"a\": 1}[\"a\"]", "({\"a\": 1}[\"a\"])"),
+        ("{1: 2} + 1;", "({1: 2} + 1)"),
+        ("{} == {}", "({} == {})"),
+        ("{ {1: 2}: 3 }", "{{1: 2}: 3}"),
+        ("{ { x }; y }", "{ { x }y }"),
+        ("{ x }; y", "{ x }"),
+        ("{ if x { 1 } }", "{ if x { 1 } }"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    // The first key of a hash is not a statement
+    let mut parser = Parser::new("{ a; : 1 }".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors()[0], ParseError::MissingExpression{found: TokenType::COLON, span: Span::new(5, 6, 1, 6)});
+}
+
+#[test]
+fn test_assign_expressions(){
+    let programs_inputs = [
+        ("x = 5", "(x = 5)"),
+        ("x = y = 1 + 2", "(x = (y = (1 + 2)))"),
+        ("x += 2 * 3", "(x += (2 * 3))"),
+        ("x -= 1; x *= 2; x /= 3", "(x -= 1)"),
+        ("arr[i] = v", "((arr[i]) = v)"),
+        ("h[\"k\"] += a || b", "((h[\"k\"]) += (a || b))"),
+        ("let z = x = 1;", "LET z = (x = 1);"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    let mut parser = Parser::new("x = 1 - 2".to_string());
+    let program = parser.parseprogramm();
+    let value = Expr::INFIX(Box::new(Expr::INTEGER(1)), Infix::MINUS, Box::new(Expr::INTEGER(2)));
+    assert_eq!(program[0], Stmt::EXPRESSION(Expr::ASSIGN(
+        Box::new(Expr::IDENTIFIER(Ident("x".to_string()))),
+        None,
+        Box::new(value),
+    )));
+
+    let mut parser = Parser::new("1 = 2; f(x) += 1; a + b = c; y = 3;".to_string());
+    let program = parser.parseprogramm();
+    assert_eq!(program.len(), 1);
+    let errors = parser.get_errors();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0], ParseError::InvalidAssignmentTarget{target: Expr::INTEGER(1), span: Span::new(2, 3, 1, 3)});
+    assert_eq!(errors[1].to_string(), "1:13: Cannot assign to f(x)");
+    assert_eq!(errors[2].to_string(), "1:25: Cannot assign to (a + b)");
+}
+
+#[test]
+fn test_while_statements(){
+    let programs_inputs = [
+        ("while (x < 10) { x += 1; }", "WHILE (x < 10) { (x += 1) }"),
+        ("while x { if x { break; } continue; }", "WHILE x { if x { BREAK; }CONTINUE; }"),
+        ("while a { while b { break } }", "WHILE a { WHILE b { BREAK; } }"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(program.len(), 1);
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    let mut parser = Parser::new("while true { continue; }".to_string());
+    let program = parser.parseprogramm();
+    assert_eq!(program[0], Stmt::WHILE(Expr::BOOLEAN(true), BlockStatement(vec![Stmt::CONTINUE])));
+
+    // Loops around a function literal do not enclose its body
+    let mut parser = Parser::new("break; while x { let f = fn() { continue; }; break; } continue".to_string());
+    let program = parser.parseprogramm();
+    assert_eq!(program.len(), 1);
+    let errors = parser.get_errors();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0], ParseError::OutsideLoop{keyword: "break".to_string(), span: Span::new(0, 5, 1, 1)});
+    assert_eq!(errors[1].to_string(), "1:33: continue can only be used inside a loop");
+    assert_eq!(errors[2].to_string(), "1:55: continue can only be used inside a loop");
+}
+
+#[test]
+fn test_range_expressions(){
+    let programs_inputs = [
+        ("0..10", "(0..10)"),
+        ("0..=n", "(0..=n)"),
+        ("a + 1..b * 2", "((a + 1)..(b * 2))"),
+        ("x = 0..len(arr)", "(x = (0..len(arr)))"),
+        ("a..b == c", "(a..(b == c))"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    let mut parser = Parser::new("1..=2".to_string());
+    let program = parser.parseprogramm();
+    assert_eq!(program[0], Stmt::EXPRESSION(Expr::RANGE(
+        Box::new(Expr::INTEGER(1)),
+        Box::new(Expr::INTEGER(2)),
+        true,
+    )));
+
+    // Ranges are not associative
+    let inputs = ["a..b..c", "0..=1..2", "x = 0..1..=2"];
+    for given in inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        parser.parseprogramm();
+        assert!(matches!(parser.get_errors()[0], ParseError::ChainedRange{..}), "{}", given);
+    }
+    let mut parser = Parser::new("a..b..c".to_string());
+    parser.parseprogramm();
+    assert_eq!(parser.get_errors().len(), 1);
+    assert_eq!(parser.get_errors()[0].to_string(), "1:5: Ranges cannot be chained, use parentheses");
+
+    let mut parser = Parser::new("(a..b)..c".to_string());
+    let program = parser.parseprogramm();
+    assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+    assert_eq!(program[0].to_string(), "((a..b)..c)");
+}
+
+#[test]
+fn test_for_statements(){
+    let programs_inputs = [
+        ("for (i in 0..10) { sum += i; }", "FOR i IN (0..10) { (sum += i) }"),
+        ("for x in [1, 2] { if x { continue } }", "FOR x IN [1, 2] { if x { CONTINUE; } }"),
+        ("for (k in keys(h)) { while k { break; } break; }", "FOR k IN keys(h) { WHILE k { BREAK; }BREAK; }"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(program.len(), 1);
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    let mut parser = Parser::new("for (1 in a) { } for (i a) { } for (i in a { } let y = 1;".to_string());
+    let program = parser.parseprogramm();
+    assert_eq!(program.len(), 1);
+    let errors = parser.get_errors();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0].to_string(), "1:6: Expected token IDENT was not found - Found INT(1) instead");
+    assert_eq!(errors[1].to_string(), "1:25: Expected token IN was not found - Found IDENT(\"a\") instead");
+    assert_eq!(errors[2].to_string(), "1:44: Expected token RPAREN was not found - Found LBRACE instead");
+}
+
+#[test]
+fn test_trailing_commas(){
+    let programs_inputs = [
+        ("[1, 2,]", "[1, 2]"),
+        ("f(1, 2,)", "f(1,2)"),
+        ("fn(a, b = 1,) { a }", "fn (a,b = 1){ a }"),
+        ("let h = {\"a\": 1,}", "LET h = {\"a\": 1};"),
+    ];
+    for (given, expected) in programs_inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        let program = parser.parseprogramm();
+        assert!(!parser.has_errors(), "{:?}", parser.get_errors());
+        assert_eq!(*expected, program[0].to_string());
+    }
+
+    // A comma must follow an element
+    let inputs = ["[,]", "f(1,,)", "let h = {,}", "fn(,) { }"];
+    for given in inputs.iter() {
+        let mut parser = Parser::new(given.to_string());
+        parser.parseprogramm();
+        assert!(parser.has_errors(), "{}", given);
+    }
+
+    let mut parser = Parser::new("fn(a, ...rest,) { }; fn(...rest, a,) { }".to_string());
+    let program = parser.parseprogramm();
+    assert_eq!(program[0].to_string(), "fn (a,...rest){  }");
+    let errors = parser.get_errors();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], ParseError::MisplacedRestParameter{..}));
+}